@@ -0,0 +1,216 @@
+## Formatierung
+
+decimal-separator = ,
+# strftime-Muster von chrono
+date-format = %d.%m.%Y
+size-bytes = { $size } B
+size-kib = { $size } KiB
+size-mib = { $size } MiB
+size-gib = { $size } GiB
+size-tib = { $size } TiB
+size-unknown = Größe unbekannt
+rate-per-second = { $size }/s
+rate-unlimited = Unbegrenzt
+duration-seconds = { $seconds } s
+duration-minutes = { $minutes } min { $seconds } s
+duration-hours = { $hours } h { $minutes } min
+transfer = { $done }, { $rate }
+transfer-of = { $done } von { $total }
+transfer-eta = { $done }, { $rate }, noch { $left }
+
+## Versionsliste
+
+install-to = Installieren nach
+show-prereleases = Vorabversionen anzeigen
+limit = Limit
+install-from-file = Aus Datei installieren…
+cache = Cache
+log = Protokoll
+settings = Einstellungen
+refresh = Aktualisieren
+search-tags = Tags durchsuchen
+update-available = { $source } { $name } ist verfügbar
+install = Installieren
+install-now = Jetzt installieren
+install-downloaded = Heruntergeladene installieren
+cancel = Abbrechen
+remove = Entfernen
+verify = Prüfen
+repair = Reparieren
+in-use = In Verwendung
+queued = In der Warteschlange
+starting = Wird gestartet
+verifying = Wird geprüft
+extracting = Wird entpackt
+signed-by = signiert von { $key }
+not-for = Nicht für { $kind }
+local-file = Lokale Datei
+installed = Installiert
+unknown-version = unbekannte Version
+incomplete-installation = unvollständige Installation
+not-verifiable = nicht prüfbar
+set-default = Als Standard festlegen
+steam-prompt = Steam muss geschlossen werden, um die Konfiguration zu ändern
+confirm-remove = { $names } entfernen?
+steam-shut-down = Steam beenden
+tarball = Tarball
+choose-destination = Installationsziel wählen
+
+badge-installed = installiert
+badge-uninstalled = nicht installiert
+badge-downloaded = heruntergeladen
+badge-downloading = wird heruntergeladen
+badge-installing = wird installiert
+
+filter-all = Alle Versionen
+filter-installed = Installiert
+filter-not-installed = Nicht installiert
+filter-in-use = In Verwendung
+filter-prerelease = Vorabversionen
+sort-version = Neueste Version
+sort-published = Zuletzt veröffentlicht
+sort-size = Größter Download
+
+channel-stable = Stabil
+channel-prerelease = Vorabversion
+channel-nightly = Nightly
+
+report-intact = alle Dateien intakt
+report-changes = { $missing } fehlend, { $modified } geändert, { $extra } zusätzliche Dateien
+
+## Statuszeile
+
+status-loading = Verfügbare Versionen werden geladen
+status-scanning = Installierte Pakete im Dateisystem werden geprüft
+status-load-failed = Daten konnten nicht geladen werden
+status-scan-failed = Dateien im lokalen Ordner konnten nicht geprüft werden
+status-done = Fertig
+status-cancelled = { $name } abgebrochen
+status-tool-in-use = { $name } wird von Steam verwendet, bitte zuerst ein anderes Tool wählen
+status-removed = { $name } entfernt
+status-remove-failed = { $name } konnte nicht entfernt werden: { $error }
+status-no-destination = Kein Installationsziel ausgewählt
+status-installed = { $name } installiert
+status-install-failed = { $name } konnte nicht installiert werden: { $error }
+status-cleanup-failed = { $destination } konnte nicht aufgeräumt werden: { $error }
+status-verified = { $name }: { $report }
+status-verify-failed = { $name } konnte nicht geprüft werden: { $error }
+status-repairing = { $path } wird repariert
+status-repaired = { $name } repariert: { $report }
+status-repair-failed = { $name } konnte nicht repariert werden: { $error }
+status-steam-updated = Steam-Konfiguration aktualisiert
+status-steam-running = Steam läuft und würde die Änderung beim Beenden überschreiben
+status-steam-update-failed = Steam-Konfiguration konnte nicht aktualisiert werden
+status-steam-waiting = Warten, bis Steam beendet ist
+status-steam-shutdown-failed = Steam wurde nicht beendet
+status-destination-exists = Ein Ziel namens { $name } existiert bereits
+status-destination-invalid = Ziel kann nicht hinzugefügt werden: { $error }
+status-cache-remove-failed = Tarball konnte nicht aus dem Cache entfernt werden: { $error }
+status-cache-clear-failed = Cache konnte nicht geleert werden: { $error }
+status-settings-saved = Einstellungen gespeichert
+status-settings-save-failed = Einstellungen konnten nicht gespeichert werden: { $error }
+
+## Einstellungen
+
+install-destinations = Installationsziele
+detect = Erkennen
+destination-name = Name
+destination-folder = Ordner
+browse = Durchsuchen…
+add = Hinzufügen
+default-destination = Standardziel
+theme = Design
+theme-dark = Dunkel
+theme-light = Hell
+language = Sprache
+language-system = Systemstandard
+sources = Quellen
+updates-from = Updates aus
+parallel-downloads = Parallele Downloads: { $count }
+retention-all = Alle installierten Versionen behalten
+retention-newest = { $count ->
+    [one] Nur die neueste Version je Quelle behalten
+   *[other] Die neuesten { $count } Versionen je Quelle behalten
+}
+proxy = Proxy
+proxy-user = Benutzer
+proxy-password = Passwort
+no-proxy = Kein Proxy für, z. B. localhost, .corp.example
+speed-limit = Download-Geschwindigkeitslimit
+cache-disabled = Heruntergeladene Tarballs nicht behalten
+cache-limit = Bis zu { $size } heruntergeladene Tarballs behalten
+update-policy = Wenn `proton_downloader check` ein Update findet
+policy-notify = Benachrichtigen
+policy-install = Automatisch installieren
+check-interval = { $hours ->
+    [one] Höchstens jede Stunde prüfen
+   *[other] Höchstens alle { $hours } Stunden prüfen
+}
+save = Speichern
+
+## Cache und Protokoll
+
+cached-tarballs = Zwischengespeicherte Tarballs
+clear = Leeren
+back = Zurück
+cache-used = { $used } von { $limit } belegt
+log-directory = Protokolldateien liegen in { $path }
+log-all-jobs = Alle Aufträge
+
+## Benachrichtigungen
+
+notify-available = { $name } ist verfügbar
+notify-available-body = Eine neue Version von { $source } wurde veröffentlicht.
+notify-installed = { $name } installiert
+notify-installed-body = Starte den Launcher neu, um sie zu verwenden.
+notify-install-failed = { $name } konnte nicht installiert werden
+timer-installed = Update-Timer in { $path } installiert
+
+## Fehler
+
+error-network = Netzwerkanfrage fehlgeschlagen
+error-filesystem = Zugriff auf das Dateisystem fehlgeschlagen
+error-download = Download fehlgeschlagen
+error-checksum = Prüfsumme des heruntergeladenen Tarballs stimmt nicht überein
+error-insufficient-space = nicht genug Platz in { $path }: { $needed } benötigt, { $available } verfügbar
+error-steam-running = Steam läuft
+error-steam-shutdown = Steam wurde nicht beendet
+error-steam-config = Steam-Konfiguration konnte nicht aktualisiert werden
+error-settings = ungültige Einstellungen: { $reason }
+error-not-writable = { $path } ist nicht beschreibbar
+error-unsafe-archive = Archiv enthält Pfade außerhalb des Installationsordners
+error-manifest-missing = kein Installationsmanifest in { $path }
+error-proxy = ungültige Proxy-Einstellungen: { $reason }
+error-repair-unavailable = kein Tarball zum Reparieren verfügbar
+error-signature-missing = Version hat keine Signatur eines vertrauenswürdigen Schlüssels
+error-signature-invalid = Signatur passt zu keinem vertrauenswürdigen Schlüssel
+error-invalid-transition = eine Version kann nicht von { $from } zu { $to } wechseln
+error-timer = Update-Timer konnte nicht eingerichtet werden: { $reason }
+error-settings-too-new = Einstellungsversion { $version } ist neuer, als diese Version unterstützt
+error-settings-migration = keine Migration von Einstellungsversion { $version }
+error-no-config-dir = kein Konfigurationsverzeichnis
+error-no-destination = kein Installationsziel konfiguriert
+error-destination-name = Ziel braucht einen Namen
+error-destination-path = der Zielpfad muss absolut sein
+error-no-home = kein Home-Verzeichnis
+error-systemctl = systemctl --user { $command } fehlgeschlagen
+error-proxy-scheme = nicht unterstütztes Schema { $scheme }
+error-proxy-user = Proxy-URL kann keinen Benutzernamen haben
+error-proxy-password = Proxy-URL kann kein Passwort haben
+
+## Befehlspalette
+
+palette-placeholder = Befehl oder Versions-Tag eingeben
+palette-no-matches = Keine Treffer
+action-next = Nächste Version
+action-previous = Vorherige Version
+action-first = Erste Version
+action-last = Letzte Version
+action-install = Ausgewählte Version installieren
+action-remove = Ausgewählte Version entfernen
+action-verify = Ausgewählte Version prüfen
+action-refresh = Versionen aktualisieren
+action-search = Tags durchsuchen
+action-palette = Befehlspalette
+action-close = Schließen
+action-toggle-prereleases = Vorabversionen ein- oder ausblenden
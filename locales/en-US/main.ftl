@@ -0,0 +1,216 @@
+## Formatting
+
+decimal-separator = .
+# chrono strftime pattern
+date-format = %Y-%m-%d
+size-bytes = { $size } B
+size-kib = { $size } KiB
+size-mib = { $size } MiB
+size-gib = { $size } GiB
+size-tib = { $size } TiB
+size-unknown = unknown size
+rate-per-second = { $size }/s
+rate-unlimited = Unlimited
+duration-seconds = { $seconds }s
+duration-minutes = { $minutes }m { $seconds }s
+duration-hours = { $hours }h { $minutes }m
+transfer = { $done }, { $rate }
+transfer-of = { $done } of { $total }
+transfer-eta = { $done }, { $rate }, { $left } left
+
+## Release list
+
+install-to = Install to
+show-prereleases = Show pre-releases
+limit = Limit
+install-from-file = Install from file…
+cache = Cache
+log = Log
+settings = Settings
+refresh = Refresh
+search-tags = Search tags
+update-available = { $source } { $name } is available
+install = Install
+install-now = Install now
+install-downloaded = Install downloaded
+cancel = Cancel
+remove = Remove
+verify = Verify
+repair = Repair
+in-use = In use
+queued = Queued
+starting = Starting
+verifying = Verifying
+extracting = Extracting
+signed-by = signed by { $key }
+not-for = Not for { $kind }
+local-file = Local file
+installed = Installed
+unknown-version = unknown version
+incomplete-installation = incomplete installation
+not-verifiable = not verifiable
+set-default = Set as default
+steam-prompt = Steam has to be closed to change its configuration
+confirm-remove = Remove { $names }?
+steam-shut-down = Shut down Steam
+tarball = Tarball
+choose-destination = Choose install destination
+
+badge-installed = installed
+badge-uninstalled = not installed
+badge-downloaded = downloaded
+badge-downloading = downloading
+badge-installing = installing
+
+filter-all = All releases
+filter-installed = Installed
+filter-not-installed = Not installed
+filter-in-use = In use
+filter-prerelease = Pre-releases
+sort-version = Newest version
+sort-published = Recently published
+sort-size = Largest download
+
+channel-stable = Stable
+channel-prerelease = Pre-release
+channel-nightly = Nightly
+
+report-intact = all files intact
+report-changes = { $missing } missing, { $modified } modified, { $extra } extra files
+
+## Status line
+
+status-loading = Loading available releases
+status-scanning = Checking installed packages in file system
+status-load-failed = Failed to load data
+status-scan-failed = Failed to check files in local folder
+status-done = Done
+status-cancelled = Cancelled { $name }
+status-tool-in-use = { $name } is used by Steam, pick another tool first
+status-removed = Removed { $name }
+status-remove-failed = Failed to remove { $name }: { $error }
+status-no-destination = No install destination selected
+status-installed = Installed { $name }
+status-install-failed = Failed to install { $name }: { $error }
+status-cleanup-failed = Failed to clean up { $destination }: { $error }
+status-verified = { $name }: { $report }
+status-verify-failed = Failed to verify { $name }: { $error }
+status-repairing = Repairing { $path }
+status-repaired = Repaired { $name }: { $report }
+status-repair-failed = Failed to repair { $name }: { $error }
+status-steam-updated = Steam configuration updated
+status-steam-running = Steam is running and would overwrite the change when it exits
+status-steam-update-failed = Failed to update Steam configuration
+status-steam-waiting = Waiting for Steam to exit
+status-steam-shutdown-failed = Steam did not shut down
+status-destination-exists = A destination named { $name } already exists
+status-destination-invalid = Cannot add destination: { $error }
+status-cache-remove-failed = Failed to remove cached tarball: { $error }
+status-cache-clear-failed = Failed to clear the cache: { $error }
+status-settings-saved = Settings saved
+status-settings-save-failed = Failed to save settings: { $error }
+
+## Settings
+
+install-destinations = Install destinations
+detect = Detect
+destination-name = Name
+destination-folder = Folder
+browse = Browse…
+add = Add
+default-destination = Default destination
+theme = Theme
+theme-dark = Dark
+theme-light = Light
+language = Language
+language-system = System default
+sources = Sources
+updates-from = Updates from
+parallel-downloads = Parallel downloads: { $count }
+retention-all = Keep all installed versions
+retention-newest = { $count ->
+    [one] Keep the newest version per source
+   *[other] Keep the newest { $count } versions per source
+}
+proxy = Proxy
+proxy-user = User
+proxy-password = Password
+no-proxy = No proxy for, e.g. localhost, .corp.example
+speed-limit = Download speed limit
+cache-disabled = Don't keep downloaded tarballs
+cache-limit = Keep up to { $size } of downloaded tarballs
+update-policy = When `proton_downloader check` finds an update
+policy-notify = Notify me
+policy-install = Install automatically
+check-interval = { $hours ->
+    [one] Check at most every hour
+   *[other] Check at most every { $hours } hours
+}
+save = Save
+
+## Cache and log
+
+cached-tarballs = Cached tarballs
+clear = Clear
+back = Back
+cache-used = { $used } of { $limit } used
+log-directory = Log files are kept in { $path }
+log-all-jobs = All jobs
+
+## Notifications
+
+notify-available = { $name } is available
+notify-available-body = A new { $source } release was published.
+notify-installed = Installed { $name }
+notify-installed-body = Restart the launcher to use it.
+notify-install-failed = Failed to install { $name }
+timer-installed = Installed the update timer in { $path }
+
+## Errors
+
+error-network = network request failed
+error-filesystem = file system access failed
+error-download = download failed
+error-checksum = checksum of the downloaded tarball does not match
+error-insufficient-space = not enough space in { $path }: { $needed } needed, { $available } available
+error-steam-running = Steam is running
+error-steam-shutdown = Steam did not shut down
+error-steam-config = failed to update the Steam configuration
+error-settings = invalid settings: { $reason }
+error-not-writable = { $path } is not writable
+error-unsafe-archive = archive contains paths outside of the install folder
+error-manifest-missing = no install manifest in { $path }
+error-proxy = invalid proxy settings: { $reason }
+error-repair-unavailable = no tarball available to repair from
+error-signature-missing = release has no signature from a trusted key
+error-signature-invalid = signature does not match any trusted key
+error-invalid-transition = a release can't go from { $from } to { $to }
+error-timer = failed to set up the update timer: { $reason }
+error-settings-too-new = settings version { $version } is newer than this release supports
+error-settings-migration = no migration from settings version { $version }
+error-no-config-dir = no configuration directory
+error-no-destination = no install destination configured
+error-destination-name = destination needs a name
+error-destination-path = the destination path has to be absolute
+error-no-home = no home directory
+error-systemctl = systemctl --user { $command } failed
+error-proxy-scheme = unsupported scheme { $scheme }
+error-proxy-user = proxy URL can't have a user name
+error-proxy-password = proxy URL can't have a password
+
+## Command palette
+
+palette-placeholder = Type a command or release tag
+palette-no-matches = No matches
+action-next = Next release
+action-previous = Previous release
+action-first = First release
+action-last = Last release
+action-install = Install selected release
+action-remove = Remove selected release
+action-verify = Verify selected release
+action-refresh = Refresh releases
+action-search = Search tags
+action-palette = Command palette
+action-close = Close
+action-toggle-prereleases = Show or hide pre-releases
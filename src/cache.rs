@@ -0,0 +1,208 @@
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const INDEX_FILE: &str = "index.toml";
+
+/// Serializes read-modify-write cycles on the index between parallel installs.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// A verified tarball, stored under its SHA-512 so the same content is only
+/// kept once.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub hash: String,
+    /// Asset file name the tarball was downloaded as.
+    pub name: String,
+    pub size: u64,
+    /// Seconds since the epoch, used for least recently used eviction.
+    pub last_used: u64,
+    /// Key that signed the tarball when it was downloaded, if any.
+    #[serde(default)]
+    pub signed_by: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Index {
+    #[serde(default)]
+    entries: Vec<CacheEntry>,
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    Some(paths::cache_dir()?.join("tarballs"))
+}
+
+/// Hashes end up as file names, so anything but a hex SHA-512 is refused.
+fn is_hash(hash: &str) -> bool {
+    hash.len() == 128 && hash.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+fn no_cache_dir() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "no cache directory")
+}
+
+fn read_index(dir: &Path) -> Index {
+    fs::read_to_string(dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_index(dir: &Path, index: &Index) -> io::Result<()> {
+    let content = toml::to_string_pretty(index).map_err(io::Error::other)?;
+    let path = dir.join(INDEX_FILE);
+    let tmp_path = path.with_extension("toml.tmp");
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, &path)
+}
+
+/// Runs `f` on the index with the lock held, dropping entries whose file has
+/// gone missing, and writes the result back.
+fn with_index<T>(f: impl FnOnce(&Path, &mut Index) -> io::Result<T>) -> io::Result<T> {
+    let dir = cache_dir().ok_or_else(no_cache_dir)?;
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut index = read_index(&dir);
+    index
+        .entries
+        .retain(|entry| dir.join(&entry.hash).is_file());
+    let result = f(&dir, &mut index)?;
+    if dir.is_dir() {
+        write_index(&dir, &index)?;
+    }
+    Ok(result)
+}
+
+/// Cached entries, most recently used first.
+pub fn entries() -> Vec<CacheEntry> {
+    let mut entries = with_index(|_, index| Ok(index.entries.clone())).unwrap_or_default();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.last_used));
+    entries
+}
+
+/// Path of the cached tarball with the given SHA-512, marking it as used.
+/// The caller still has to verify it, the file may have been damaged.
+pub fn lookup(hash: &str) -> Option<PathBuf> {
+    if !is_hash(hash) {
+        return None;
+    }
+    with_index(|dir, index| {
+        let entry = index.entries.iter_mut().find(|entry| entry.hash == hash);
+        Ok(entry.map(|entry| {
+            entry.last_used = now();
+            dir.join(&entry.hash)
+        }))
+    })
+    .ok()
+    .flatten()
+}
+
+/// Finds a tarball by asset name, for reinstalling when the checksum can't be
+/// fetched. The entry holds the hash to verify it against.
+pub fn lookup_name(name: &str) -> Option<(PathBuf, CacheEntry)> {
+    with_index(|dir, index| {
+        let entry = index.entries.iter_mut().find(|entry| entry.name == name);
+        Ok(entry.map(|entry| {
+            entry.last_used = now();
+            (dir.join(&entry.hash), entry.clone())
+        }))
+    })
+    .ok()
+    .flatten()
+}
+
+/// Copies a verified tarball into the cache and evicts the least recently
+/// used entries until the cache fits in `limit` bytes. Tarballs larger than
+/// the whole cache are not stored.
+pub fn store(
+    hash: &str,
+    name: &str,
+    tarball: &Path,
+    limit: u64,
+    signed_by: Option<&str>,
+) -> io::Result<()> {
+    let size = fs::metadata(tarball)?.len();
+    if size > limit || !is_hash(hash) {
+        return Ok(());
+    }
+
+    with_index(|dir, index| {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(hash);
+        if !path.is_file() {
+            let part = dir.join(format!("{}.part", hash));
+            fs::copy(tarball, &part)?;
+            fs::rename(&part, &path)?;
+        }
+
+        let previous = index
+            .entries
+            .iter()
+            .position(|entry| entry.hash == hash)
+            .map(|position| index.entries.remove(position));
+        index.entries.insert(
+            0,
+            CacheEntry {
+                hash: hash.to_string(),
+                name: name.to_string(),
+                size,
+                last_used: now(),
+                signed_by: signed_by
+                    .map(String::from)
+                    .or(previous.and_then(|entry| entry.signed_by)),
+            },
+        );
+        evict_entries(dir, index, limit)
+    })
+}
+
+fn evict_entries(dir: &Path, index: &mut Index, limit: u64) -> io::Result<()> {
+    index
+        .entries
+        .sort_by_key(|entry| std::cmp::Reverse(entry.last_used));
+    let mut total = 0;
+    let mut kept = Vec::new();
+    for entry in index.entries.drain(..) {
+        if total + entry.size <= limit {
+            total += entry.size;
+            kept.push(entry);
+        } else {
+            fs::remove_file(dir.join(&entry.hash))?;
+        }
+    }
+    index.entries = kept;
+    Ok(())
+}
+
+/// Shrinks the cache to `limit` bytes, e.g. after the limit was lowered.
+pub fn evict(limit: u64) -> io::Result<()> {
+    with_index(|dir, index| evict_entries(dir, index, limit))
+}
+
+pub fn remove(hash: &str) -> io::Result<()> {
+    if !is_hash(hash) {
+        return Ok(());
+    }
+    with_index(|dir, index| {
+        index.entries.retain(|entry| entry.hash != hash);
+        match fs::remove_file(dir.join(hash)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    })
+}
+
+pub fn clear() -> io::Result<()> {
+    evict(0)
+}
@@ -0,0 +1,341 @@
+use crate::installed::InstalledTool;
+use crate::notify::{self, Notification};
+use crate::paths;
+use crate::proton::{proton_status, Proton};
+use crate::settings::{Config, UpdatePolicy};
+use crate::source;
+use crate::target::Destination;
+use crate::version::Version;
+use crate::{cache, install, DownloaderError};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const LAST_CHECK_FILE: &str = "last-check";
+/// Keys of the releases already announced under [`UpdatePolicy::Notify`],
+/// one per line.
+const NOTIFIED_FILE: &str = "notified";
+const UNIT_NAME: &str = "proton_downloader-check";
+
+/// Newest release in the channel each source is followed on that is newer
+/// than every installed build of the same variant and not installed yet.
+pub fn find_updates<'a>(
+    config: &Config,
+    destination: &Destination,
+    releases: &'a HashMap<String, Proton>,
+    installed: &[InstalledTool],
+) -> Vec<(&'a String, &'a Proton)> {
+    let mut updates = Vec::new();
+    for source in source::SOURCES
+        .iter()
+        .filter(|source| destination.kind.accepts(source.kind))
+    {
+        let subscribed = config.channel(source.id);
+        let installed: Vec<Version> = installed
+            .iter()
+            .filter(|tool| source.owns(&tool.folder) || source.owns(tool.name()))
+            .filter_map(|tool| tool.parsed_version())
+            .collect();
+
+        let mut newest: HashMap<Option<String>, (&String, &Proton)> = HashMap::new();
+        for (key, proton) in releases {
+            let version = match proton.get_version() {
+                Some(version) if proton.get_source() == source => version,
+                _ => continue,
+            };
+            if !subscribed.includes(proton.get_channel()) {
+                continue;
+            }
+            let current = newest
+                .get(&version.variant)
+                .and_then(|(_, p)| p.get_version());
+            if current.is_none_or(|current| version > current) {
+                newest.insert(version.variant.clone(), (key, proton));
+            }
+        }
+
+        for (key, proton) in newest.into_values() {
+            let version = match proton.get_version() {
+                Some(version) => version,
+                None => continue,
+            };
+            let same_variant = installed.iter().filter(|v| v.same_variant(version));
+            let is_newer = match same_variant.max() {
+                Some(installed) => version > installed,
+                None => false,
+            };
+            let pending = matches!(
+                proton.get_status(),
+                proton_status::Uninstalled | proton_status::Downloaded
+            );
+            if is_newer && pending {
+                updates.push((key, proton));
+            }
+        }
+    }
+    updates.sort_by(|a, b| a.0.cmp(b.0));
+    updates
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+fn state_path(name: &str) -> Option<PathBuf> {
+    Some(paths::state_dir()?.join(name))
+}
+
+fn write_state(name: &str, contents: String) -> std::io::Result<()> {
+    let path = state_path(name)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no state directory"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
+
+/// Unix time of the last successful check.
+pub fn last_check() -> Option<u64> {
+    fs::read_to_string(state_path(LAST_CHECK_FILE)?)
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn record_check(time: u64) -> std::io::Result<()> {
+    write_state(LAST_CHECK_FILE, time.to_string())
+}
+
+fn notified() -> HashSet<String> {
+    state_path(NOTIFIED_FILE)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|contents| contents.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn record_notified(keys: &HashSet<String>) -> std::io::Result<()> {
+    let mut keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+    keys.sort_unstable();
+    write_state(NOTIFIED_FILE, keys.join("\n"))
+}
+
+/// Time left until the next check is due, `None` when it is due now.
+pub fn time_until_due(config: &Config, last: Option<u64>, now: u64) -> Option<Duration> {
+    let interval = config.check_interval_hours as u64 * 3600;
+    let due = last?.checked_add(interval)?;
+    (due > now).then(|| Duration::from_secs(due - now))
+}
+
+async fn send(client: &Option<notify::Client>, notification: Notification) {
+    if let Some(client) = client {
+        if let Err(e) = client.send(&notification).await {
+            tracing::warn!("failed to show notification: {}", e);
+        }
+    }
+}
+
+/// Checks the enabled sources for updates to the builds installed in the
+/// default destination and notifies about or installs them, depending on
+/// the configured policy. Skipped when the last check is more recent than
+/// the check interval, unless `force` is set.
+pub async fn run(config: Config, force: bool) -> Result<(), DownloaderError> {
+    let now = now();
+    if !force {
+        if let Some(left) = time_until_due(&config, last_check(), now) {
+            tracing::info!(
+                "Nothing to do, the next check is due in {}",
+                install::format_duration(left)
+            );
+            return Ok(());
+        }
+    }
+
+    let destination = config
+        .default_destination()
+        .cloned()
+        .ok_or_else(|| DownloaderError::SettingsError(tr!("error-no-destination")))?;
+    let releases = crate::get_releases(config.sources.clone()).await?;
+    let installed = crate::get_installed_wrapper(Some(destination.clone())).await?;
+    let cached: HashSet<String> = cache::entries()
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect();
+
+    let mut list = HashMap::new();
+    for (source, release) in &releases {
+        let built = crate::build_release(source, release, Some(&destination), &installed, &cached);
+        if let Ok(proton) = built {
+            list.insert(proton.get_key(), proton);
+        }
+    }
+
+    let updates = find_updates(&config, &destination, &list, &installed);
+    if updates.is_empty() {
+        tracing::info!("No updates for {}", destination);
+        finish_check(now);
+        return Ok(());
+    }
+
+    // Announced releases that are installed or superseded by now are
+    // forgotten, the rest is announced only once.
+    let mut notified = notified();
+    notified.retain(|key| updates.iter().any(|(update, _)| *update == key));
+    let client = notify::Client::connect(None).await.ok();
+    let mut result = Ok(());
+    for (key, proton) in updates {
+        let source = proton.get_source();
+        match config.update_policy {
+            UpdatePolicy::Notify => {
+                if !notified.insert(key.clone()) {
+                    tracing::info!("{} was already announced", proton.get_name());
+                    continue;
+                }
+                tracing::info!("{} is available", proton.get_name());
+                let notification = Notification::new(
+                    tr!("notify-available", name = proton.get_name()),
+                    tr!("notify-available-body", source = source.name),
+                );
+                send(&client, notification).await;
+            }
+            UpdatePolicy::Install => {
+                tracing::info!("Installing {} to {}", proton.get_name(), destination);
+                let installed = install::install_now(
+                    proton,
+                    destination.path.clone(),
+                    config.cache_limit(),
+                    config.keys_for(source.id),
+                )
+                .await;
+                let notification = match installed {
+                    // Old builds are only removed from the GUI, after asking.
+                    Ok(()) => Notification::new(
+                        tr!("notify-installed", name = proton.get_name()),
+                        tr!("notify-installed-body"),
+                    ),
+                    Err(e) => {
+                        tracing::error!("Failed to install {}: {}", proton.get_name(), e);
+                        let notification = Notification::new(
+                            tr!("notify-install-failed", name = proton.get_name()),
+                            e.to_string(),
+                        )
+                        .critical();
+                        result = Err(e);
+                        notification
+                    }
+                };
+                send(&client, notification).await;
+            }
+        }
+    }
+    if config.update_policy == UpdatePolicy::Notify {
+        if let Err(e) = record_notified(&notified) {
+            tracing::warn!("failed to record the announced releases: {}", e);
+        }
+    }
+    // A failed install is retried on the next run instead of waiting for the
+    // whole interval.
+    if result.is_ok() {
+        finish_check(now);
+    }
+    result
+}
+
+fn finish_check(now: u64) {
+    if let Err(e) = record_check(now) {
+        tracing::warn!("failed to record the check time: {}", e);
+    }
+}
+
+/// Quotes an argument for an `ExecStart=` line.
+fn quote(argument: &str) -> String {
+    let escaped = argument
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%");
+    format!("\"{}\"", escaped)
+}
+
+fn service_unit(executable: &Path) -> String {
+    format!(
+        "[Unit]
+Description=Check for new Proton and Wine builds
+
+[Service]
+Type=oneshot
+ExecStart={} check
+",
+        quote(&executable.to_string_lossy())
+    )
+}
+
+/// Runs hourly, the check itself skips runs until the interval in the
+/// settings has passed.
+const TIMER_UNIT: &str = "[Unit]
+Description=Periodic check for new Proton and Wine builds
+
+[Timer]
+OnCalendar=hourly
+RandomizedDelaySec=10min
+Persistent=true
+
+[Install]
+WantedBy=timers.target
+";
+
+fn systemctl(args: &[&str]) -> Result<(), DownloaderError> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .status()
+        .map_err(|e| DownloaderError::TimerError(e.to_string()))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(DownloaderError::TimerError(tr!(
+            "error-systemctl",
+            command = args.join(" ")
+        )))
+    }
+}
+
+/// Writes a user service running `check` with this executable and a timer
+/// for it, then enables the timer.
+pub fn install_timer() -> Result<PathBuf, DownloaderError> {
+    let dir = paths::systemd_user_dir()
+        .ok_or_else(|| DownloaderError::TimerError(tr!("error-no-home")))?;
+    let executable =
+        std::env::current_exe().map_err(|e| DownloaderError::TimerError(e.to_string()))?;
+    let write = |extension: &str, content: &str| {
+        fs::write(dir.join(format!("{}.{}", UNIT_NAME, extension)), content)
+            .map_err(|e| DownloaderError::TimerError(e.to_string()))
+    };
+
+    fs::create_dir_all(&dir).map_err(|e| DownloaderError::TimerError(e.to_string()))?;
+    write("service", &service_unit(&executable))?;
+    write("timer", TIMER_UNIT)?;
+    systemctl(&["daemon-reload"])?;
+    systemctl(&["enable", "--now", &format!("{}.timer", UNIT_NAME)])?;
+    Ok(dir)
+}
+
+/// Disables the timer and deletes both units.
+pub fn remove_timer() -> Result<(), DownloaderError> {
+    let dir = paths::systemd_user_dir()
+        .ok_or_else(|| DownloaderError::TimerError(tr!("error-no-home")))?;
+    let timer = format!("{}.timer", UNIT_NAME);
+    // Fails when the timer was never enabled, which is fine here.
+    let _ = systemctl(&["disable", "--now", &timer]);
+    for extension in ["timer", "service"] {
+        let path = dir.join(format!("{}.{}", UNIT_NAME, extension));
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| DownloaderError::TimerError(e.to_string()))?;
+        }
+    }
+    systemctl(&["daemon-reload"])
+}
@@ -0,0 +1,74 @@
+use crate::ratelimit::Rate;
+
+const USAGE: &str = "Usage: proton_downloader [OPTIONS] [COMMAND]
+
+Commands:
+  check          Look for updates without opening the window
+  install-timer  Run `check` periodically with a systemd user timer
+  remove-timer   Disable and remove the timer again
+
+Options:
+      --limit-rate <RATE>  Cap downloads at RATE bytes per second, e.g. 500K or 2M
+      --force              Check even if the last check was recent
+  -h, --help               Print this help";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Gui,
+    Check,
+    InstallTimer,
+    RemoveTimer,
+}
+
+#[derive(Debug, Default)]
+pub struct Args {
+    pub mode: Mode,
+    pub limit_rate: Option<Rate>,
+    pub force: bool,
+}
+
+pub enum ParseResult {
+    Run(Args),
+    Help(&'static str),
+    Error(String),
+}
+
+pub fn parse(args: impl IntoIterator<Item = String>) -> ParseResult {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+
+        match name.as_str() {
+            "-h" | "--help" => return ParseResult::Help(USAGE),
+            "--force" => parsed.force = true,
+            "check" | "install-timer" | "remove-timer" if parsed.mode == Mode::Gui => {
+                parsed.mode = match name.as_str() {
+                    "check" => Mode::Check,
+                    "install-timer" => Mode::InstallTimer,
+                    _ => Mode::RemoveTimer,
+                };
+            }
+            "--limit-rate" => {
+                let value = match inline.or_else(|| args.next()) {
+                    Some(value) => value,
+                    None => return ParseResult::Error(String::from("--limit-rate needs a value")),
+                };
+                match Rate::parse(&value) {
+                    Some(rate) => parsed.limit_rate = Some(rate),
+                    None => {
+                        return ParseResult::Error(format!("invalid rate '{}'", value));
+                    }
+                }
+            }
+            _ => return ParseResult::Error(format!("unknown argument '{}'\n\n{}", name, USAGE)),
+        }
+    }
+
+    ParseResult::Run(parsed)
+}
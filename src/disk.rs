@@ -0,0 +1,110 @@
+use crate::installed::format_size;
+use crate::DownloaderError;
+use std::ffi::CString;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::mem::MaybeUninit;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+// GE-Proton tarballs unpack to roughly three times their compressed size,
+// the extra half is head room for the filesystem's own overhead.
+const UNPACK_RATIO_NUMERATOR: u64 = 7;
+const UNPACK_RATIO_DENOMINATOR: u64 = 2;
+
+/// The target directory might not exist before the first install, so space
+/// is measured on the closest ancestor that does.
+fn existing_ancestor(path: &Path) -> PathBuf {
+    let mut current = path;
+    while !current.exists() {
+        match current.parent() {
+            Some(parent) => current = parent,
+            None => break,
+        }
+    }
+    current.to_path_buf()
+}
+
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    let path = existing_ancestor(path);
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    let result = unsafe { libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let stat = unsafe { stat.assume_init() };
+
+    // Both fields are narrower than u64 on 32-bit targets.
+    #[allow(clippy::unnecessary_cast)]
+    let available = stat.f_bavail as u64 * stat.f_frsize as u64;
+    Ok(available)
+}
+
+fn same_filesystem(first: &Path, second: &Path) -> bool {
+    let first = fs::metadata(existing_ancestor(first));
+    let second = fs::metadata(existing_ancestor(second));
+    match (first, second) {
+        (Ok(first), Ok(second)) => first.dev() == second.dev(),
+        _ => false,
+    }
+}
+
+pub fn estimate_unpacked_size(tarball_size: u64) -> u64 {
+    tarball_size * UNPACK_RATIO_NUMERATOR / UNPACK_RATIO_DENOMINATOR
+}
+
+/// Reads the uncompressed size from the gzip trailer. The field only holds
+/// the size modulo 4 GiB, so a value smaller than the archive itself means it
+/// wrapped and the estimate is used instead.
+pub fn gzip_unpacked_size(path: &Path) -> io::Result<u64> {
+    let mut file = File::open(path)?;
+    let compressed = file.metadata()?.len();
+    if compressed < 4 {
+        return Ok(0);
+    }
+
+    let mut trailer = [0u8; 4];
+    file.seek(SeekFrom::End(-4))?;
+    file.read_exact(&mut trailer)?;
+    let size = u32::from_le_bytes(trailer) as u64;
+
+    if size < compressed {
+        Ok(estimate_unpacked_size(compressed))
+    } else {
+        Ok(size)
+    }
+}
+
+pub fn require_space(path: &Path, needed: u64) -> Result<(), DownloaderError> {
+    let available = available_space(path).or(Err(DownloaderError::FilesystemError))?;
+    if available < needed {
+        Err(DownloaderError::InsufficientSpace {
+            path: path.display().to_string(),
+            needed: format_size(needed),
+            available: format_size(available),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks that the download fits into `staging` and the unpacked tool into
+/// `target`, counting both against the same disk when they share one.
+pub fn check_install_space(
+    staging: &Path,
+    target: &Path,
+    download_size: u64,
+) -> Result<(), DownloaderError> {
+    let unpacked_size = estimate_unpacked_size(download_size);
+
+    if same_filesystem(staging, target) {
+        require_space(target, download_size + unpacked_size)
+    } else {
+        require_space(staging, download_size)?;
+        require_space(target, unpacked_size)
+    }
+}
@@ -0,0 +1,133 @@
+use crate::settings::Config;
+use crate::DownloaderError;
+use futures::future::BoxFuture;
+use octocrab::service::middleware::base_uri::BaseUriLayer;
+use octocrab::{AuthState, Octocrab, OctocrabBuilder};
+use reqwest::{Client, NoProxy, Proxy, Url};
+use std::sync::RwLock;
+use std::task::{Context, Poll};
+use tower::Service;
+
+const USER_AGENT: &str = concat!("proton_downloader/", env!("CARGO_PKG_VERSION"));
+
+/// Shared by the GitHub API calls and every download, rebuilt whenever the
+/// proxy settings change.
+static CLIENT: RwLock<Option<Client>> = RwLock::new(None);
+
+fn env(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+}
+
+fn invalid(message: impl std::fmt::Display) -> DownloaderError {
+    DownloaderError::ProxyError(message.to_string())
+}
+
+/// Proxy URL from the settings with the separately stored credentials put
+/// in, so they also reach SOCKS5 proxies.
+fn configured_proxy(config: &Config) -> Result<Option<String>, DownloaderError> {
+    let url = match config.proxy.as_deref().map(str::trim) {
+        Some(url) if !url.is_empty() => url,
+        _ => return Ok(None),
+    };
+    let mut url = Url::parse(url).map_err(invalid)?;
+    if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+        return Err(invalid(tr!("error-proxy-scheme", scheme = url.scheme())));
+    }
+    if let Some(user) = config.proxy_user.as_deref().filter(|user| !user.is_empty()) {
+        url.set_username(user)
+            .map_err(|_| invalid(tr!("error-proxy-user")))?;
+        url.set_password(config.proxy_password.as_deref())
+            .map_err(|_| invalid(tr!("error-proxy-password")))?;
+    }
+    Ok(Some(url.to_string()))
+}
+
+/// Builds a client for the proxy in `config`, falling back to the usual
+/// `HTTPS_PROXY`, `HTTP_PROXY`, `ALL_PROXY` and `NO_PROXY` variables.
+pub fn build_client(config: &Config) -> Result<Client, DownloaderError> {
+    let no_proxy = if config.no_proxy.is_empty() {
+        env(&["NO_PROXY", "no_proxy"])
+    } else {
+        Some(config.no_proxy.join(","))
+    };
+    let no_proxy = no_proxy.and_then(|list| NoProxy::from_string(&list));
+
+    let mut proxies = Vec::new();
+    if let Some(url) = configured_proxy(config)? {
+        proxies.push(Proxy::all(url));
+    } else {
+        if let Some(url) = env(&["HTTPS_PROXY", "https_proxy"]) {
+            proxies.push(Proxy::https(url));
+        }
+        if let Some(url) = env(&["HTTP_PROXY", "http_proxy"]) {
+            proxies.push(Proxy::http(url));
+        }
+        if let Some(url) = env(&["ALL_PROXY", "all_proxy"]) {
+            proxies.push(Proxy::all(url));
+        }
+    }
+
+    // The environment was read above, reqwest must not pick it up again.
+    let mut builder = Client::builder().user_agent(USER_AGENT).no_proxy();
+    for proxy in proxies {
+        builder = builder.proxy(proxy.map_err(invalid)?.no_proxy(no_proxy.clone()));
+    }
+    builder.build().map_err(invalid)
+}
+
+pub fn configure(config: &Config) -> Result<(), DownloaderError> {
+    let client = build_client(config)?;
+    *CLIENT.write().unwrap_or_else(|e| e.into_inner()) = Some(client);
+    Ok(())
+}
+
+pub fn client() -> Client {
+    let client = CLIENT.read().unwrap_or_else(|e| e.into_inner()).clone();
+    client.unwrap_or_else(|| build_client(&Config::default()).unwrap_or_else(|_| Client::new()))
+}
+
+pub async fn get(url: &str) -> reqwest::Result<reqwest::Response> {
+    client().get(url).send().await
+}
+
+/// Sends octocrab's requests through [`client`], so the API calls follow the
+/// same proxy settings as the downloads.
+#[derive(Clone)]
+struct Shared;
+
+impl Service<http::Request<String>> for Shared {
+    type Response = http::Response<hyper::Body>;
+    type Error = reqwest::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<String>) -> Self::Future {
+        Box::pin(async move {
+            let response = client().execute(request.try_into()?).await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let mut converted = http::Response::new(hyper::Body::from(response.bytes().await?));
+            *converted.status_mut() = status;
+            *converted.headers_mut() = headers;
+            Ok(converted)
+        })
+    }
+}
+
+/// GitHub API client on top of the shared client.
+pub fn github() -> Result<Octocrab, DownloaderError> {
+    OctocrabBuilder::new_empty()
+        .with_service(Shared)
+        .with_layer(&BaseUriLayer::new(http::Uri::from_static(
+            "https://api.github.com",
+        )))
+        .with_auth(AuthState::None)
+        .build()
+        .or(Err(DownloaderError::NetworkError))
+}
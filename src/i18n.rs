@@ -0,0 +1,211 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Local, TimeZone};
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::sync::{Arc, RwLock};
+use unic_langid::LanguageIdentifier;
+
+/// Looks up a message in the current language, e.g.
+/// `tr!("status-installed", name = proton.get_name())`.
+macro_rules! tr {
+    ($id:expr) => {
+        $crate::i18n::tr($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::tr($id, Some(&args))
+    }};
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Language {
+    pub id: &'static str,
+    /// Name of the language in itself, for the settings.
+    pub name: &'static str,
+    catalog: &'static str,
+}
+
+/// Languages with a catalog, the first one is the fallback for messages
+/// missing in the others.
+pub static LANGUAGES: [Language; 2] = [
+    Language {
+        id: "en-US",
+        name: "English",
+        catalog: include_str!("../locales/en-US/main.ftl"),
+    },
+    Language {
+        id: "de",
+        name: "Deutsch",
+        catalog: include_str!("../locales/de/main.ftl"),
+    },
+];
+
+struct Catalog {
+    language: &'static Language,
+    bundle: FluentBundle<FluentResource>,
+    fallback: Option<FluentBundle<FluentResource>>,
+}
+
+/// Used when a catalog's `date-format` is not a valid pattern.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+static CURRENT: RwLock<Option<Arc<Catalog>>> = RwLock::new(None);
+
+fn bundle(language: &Language) -> FluentBundle<FluentResource> {
+    let id: LanguageIdentifier = language.id.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // Isolation marks show up as boxes in the GUI's fonts.
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(language.catalog.to_string()).unwrap_or_else(
+        |(resource, errors)| {
+            tracing::warn!("errors in the {} catalog: {:?}", language.id, errors);
+            resource
+        },
+    );
+    if let Err(errors) = bundle.add_resource(resource) {
+        tracing::warn!("errors in the {} catalog: {:?}", language.id, errors);
+    }
+    bundle
+}
+
+impl Catalog {
+    fn new(language: &'static Language) -> Catalog {
+        let fallback = Some(&LANGUAGES[0])
+            .filter(|fallback| *fallback != language)
+            .map(bundle);
+        Catalog {
+            language,
+            bundle: bundle(language),
+            fallback,
+        }
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+        [Some(&self.bundle), self.fallback.as_ref()]
+            .into_iter()
+            .flatten()
+            .find_map(|bundle| {
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+                let text = bundle.format_pattern(pattern, args, &mut errors);
+                if !errors.is_empty() {
+                    tracing::debug!("formatting {}: {:?}", id, errors);
+                }
+                Some(text.into_owned())
+            })
+    }
+}
+
+fn current() -> Arc<Catalog> {
+    if let Some(catalog) = CURRENT.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return catalog.clone();
+    }
+    let catalog = Arc::new(Catalog::new(detect()));
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = Some(catalog.clone());
+    catalog
+}
+
+/// Message `id` in the current language, the id itself when no catalog has
+/// it. Use [`tr!`] rather than calling this directly.
+pub fn tr(id: &str, args: Option<&FluentArgs>) -> String {
+    current().format(id, args).unwrap_or_else(|| id.to_string())
+}
+
+/// Language the environment asks for, e.g. through `LANG` or `LC_MESSAGES`,
+/// falling back to English.
+pub fn detect() -> &'static Language {
+    sys_locale::get_locales()
+        .filter_map(|locale| locale.parse::<LanguageIdentifier>().ok())
+        .find_map(find)
+        .unwrap_or(&LANGUAGES[0])
+}
+
+/// Exact match first, then any catalog for the same language.
+fn find(wanted: LanguageIdentifier) -> Option<&'static Language> {
+    let parsed = |language: &Language| language.id.parse::<LanguageIdentifier>().ok();
+    LANGUAGES
+        .iter()
+        .find(|language| parsed(language).as_ref() == Some(&wanted))
+        .or_else(|| {
+            LANGUAGES
+                .iter()
+                .find(|language| parsed(language).is_some_and(|id| id.language == wanted.language))
+        })
+}
+
+/// Switches to the language with `id`, or back to the detected one when
+/// `None` or unknown.
+pub fn set_language(id: Option<&str>) {
+    let language = id
+        .and_then(|id| id.parse().ok())
+        .and_then(find)
+        .unwrap_or_else(detect);
+    if current().language != language {
+        *CURRENT.write().unwrap_or_else(|e| e.into_inner()) =
+            Some(Arc::new(Catalog::new(language)));
+    }
+}
+
+/// Entries of the language setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanguageChoice {
+    System,
+    Language(&'static Language),
+}
+
+impl LanguageChoice {
+    pub fn all() -> Vec<LanguageChoice> {
+        std::iter::once(LanguageChoice::System)
+            .chain(LANGUAGES.iter().map(LanguageChoice::Language))
+            .collect()
+    }
+
+    pub fn from_setting(id: Option<&str>) -> LanguageChoice {
+        match id.and_then(|id| LANGUAGES.iter().find(|language| language.id == id)) {
+            Some(language) => LanguageChoice::Language(language),
+            None => LanguageChoice::System,
+        }
+    }
+
+    pub fn setting(&self) -> Option<String> {
+        match self {
+            LanguageChoice::System => None,
+            LanguageChoice::Language(language) => Some(language.id.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for LanguageChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LanguageChoice::System => write!(f, "{}", tr!("language-system")),
+            LanguageChoice::Language(language) => write!(f, "{}", language.name),
+        }
+    }
+}
+
+/// `value` with `decimals` digits after the separator of the current
+/// language.
+pub fn format_decimal(value: f64, decimals: usize) -> String {
+    let formatted = format!("{:.*}", decimals, value);
+    match formatted.split_once('.') {
+        Some((whole, fraction)) => format!("{}{}{}", whole, tr!("decimal-separator"), fraction),
+        None => formatted,
+    }
+}
+
+/// Unix time as a local date in the format of the current language.
+pub fn format_date(timestamp: i64) -> String {
+    let date = match Local.timestamp_opt(timestamp, 0).single() {
+        Some(date) => date,
+        None => return String::new(),
+    };
+    let pattern = tr!("date-format");
+    let mut items: Vec<Item> = StrftimeItems::new(&pattern).collect();
+    // A broken pattern in a catalog would make formatting panic.
+    if items.contains(&Item::Error) {
+        items = StrftimeItems::new(DATE_FORMAT).collect();
+    }
+    date.format_with_items(items.iter()).to_string()
+}
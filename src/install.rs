@@ -1,157 +1,914 @@
-use crate::Proton;
-use bytes::{Buf, BufMut, BytesMut};
-use flate2::bufread::GzDecoder;
+use crate::cache;
+use crate::disk;
+use crate::http;
+use crate::manifest::{self, Report};
+use crate::ratelimit::{self, Pacer};
+use crate::signature::{self, SignatureKind, TrustedKey};
+use crate::{DownloaderError, Proton};
+use flate2::read::GzDecoder;
 use iced::subscription;
-use sha2::{Digest, Sha512};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tar::Archive;
+use tempfile::NamedTempFile;
+use tracing::Instrument;
+use xz2::read::XzDecoder;
 
-pub fn install(release: &Proton) -> iced::Subscription<(String, Progress)> {
-    let id = release.get_name();
+const XZ_MAGIC: [u8; 6] = [0xfd, b'7', b'z', b'X', b'Z', 0x00];
+
+/// `cache_limit` is the tarball cache size in bytes, 0 skips the cache.
+/// When `keys` is not empty the release has to be signed by one of them.
+pub fn install(
+    release: &Proton,
+    target: PathBuf,
+    cache_limit: u64,
+    keys: Vec<TrustedKey>,
+) -> iced::Subscription<(String, Progress)> {
+    let id = release.get_key();
+    subscription::unfold(
+        id.clone(),
+        first_state(release, target, cache_limit, keys),
+        move |state| start_installation(id.clone(), state),
+    )
+}
+
+/// Runs the same steps as [`install`] to completion, for installs without
+/// a window to report progress to.
+pub async fn install_now(
+    release: &Proton,
+    target: PathBuf,
+    cache_limit: u64,
+    keys: Vec<TrustedKey>,
+) -> Result<(), DownloaderError> {
+    let id = release.get_key();
+    let mut state = first_state(release, target, cache_limit, keys);
+    loop {
+        let ((_, progress), next) = start_installation(id.clone(), state).await;
+        match progress {
+            Progress::Finished => return Ok(()),
+            Progress::Errored(e) => return Err(e),
+            _ => state = next,
+        }
+    }
+}
+
+fn first_state(
+    release: &Proton,
+    target: PathBuf,
+    cache_limit: u64,
+    keys: Vec<TrustedKey>,
+) -> State {
+    let tarball_url = release.get_tarball_url();
+    State::Ready {
+        checksum_url: release.get_checksum_url(),
+        cache: cache_slot(&tarball_url, cache_limit),
+        tarball_url,
+        tarball_size: release.get_tarball_size(),
+        signing: signing(release, keys),
+        target,
+    }
+}
+
+fn cache_slot(tarball_url: &str, limit: u64) -> Option<CacheSlot> {
+    Some(CacheSlot {
+        name: asset_name(tarball_url),
+        limit,
+    })
+    .filter(|_| limit > 0)
+}
+
+fn signing(release: &Proton, keys: Vec<TrustedKey>) -> Option<Signing> {
+    Some(Signing {
+        url: release.get_signature_url(),
+        keys,
+    })
+    .filter(|signing| !signing.keys.is_empty())
+}
+
+/// Installs a tarball that is already on disk, e.g. one handed over by a
+/// colleague or built by CI. It goes through the same verification and
+/// extraction as a download, with the checksum taken from a `.sha512sum` file
+/// next to it when there is one.
+pub fn install_file(
+    id: String,
+    path: PathBuf,
+    target: PathBuf,
+) -> iced::Subscription<(String, Progress)> {
     subscription::unfold(
         id.clone(),
-        State::Ready(release.get_checksum_url(), release.get_tarball_url()),
+        State::ReadyLocal { path, target },
         move |state| start_installation(id.clone(), state),
     )
 }
 
+/// Looks for `<file>.sha512sum` and, for GE style names, `<name>.sha512sum`
+/// next to `<name>.tar.gz`.
+fn local_checksum(path: &Path) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+    let mut candidates = vec![path.with_file_name(format!("{}.sha512sum", file_name))];
+    for extension in [".tar.gz", ".tar.xz"] {
+        if let Some(stem) = file_name.strip_suffix(extension) {
+            candidates.push(path.with_file_name(format!("{}.sha512sum", stem)));
+        }
+    }
+
+    let content = candidates
+        .iter()
+        .find_map(|candidate| fs::read_to_string(candidate).ok())?;
+    content.split_whitespace().next().map(String::from)
+}
+
+fn asset_name(url: &str) -> String {
+    url.rsplit('/').next().unwrap_or(url).to_string()
+}
+
+async fn fetch_signature(signing: Signing) -> Result<SignatureCheck, DownloaderError> {
+    let url = signing.url.ok_or(DownloaderError::SignatureMissing)?;
+    let kind = SignatureKind::from_asset(&url).ok_or(DownloaderError::SignatureMissing)?;
+    let response = http::get(&url)
+        .await
+        .or(Err(DownloaderError::NetworkError))?;
+    let data = response
+        .bytes()
+        .await
+        .or(Err(DownloaderError::DownloadError))?;
+
+    Ok(SignatureCheck::Pending {
+        kind,
+        data: data.to_vec(),
+        keys: signing.keys,
+    })
+}
+
+/// Starts verification of a cached tarball instead of downloading it.
+fn from_cache(
+    id: String,
+    path: PathBuf,
+    checksum: String,
+    signature: Option<SignatureCheck>,
+    target: PathBuf,
+) -> ((String, Progress), State) {
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    if let Err(e) = disk::require_space(&target, disk::estimate_unpacked_size(size)) {
+        return ((id, Progress::Errored(e)), State::Finished);
+    }
+    (
+        (id, Progress::CheckIntegrity),
+        State::CheckIntegrity {
+            checksum: Some(checksum),
+            tarball: Tarball::Cached(path),
+            cache: None,
+            signature,
+            target,
+        },
+    )
+}
+
+fn staging_dir() -> PathBuf {
+    std::env::temp_dir()
+}
+
+/// Runs one step of an install in a span named after the job, so everything
+/// logged on the way can be told apart from other running installs.
 async fn start_installation(id: String, state: State) -> ((String, Progress), State) {
+    let span = tracing::info_span!("install", job = %id);
+    let ((id, progress), state) = step(id, state).instrument(span.clone()).await;
+    span.in_scope(|| match &progress {
+        Progress::Advanced(_) => {}
+        Progress::Signed(key) => tracing::info!(key = %key, "signature verified"),
+        Progress::Finished => tracing::info!("installed"),
+        Progress::Errored(e) => tracing::error!("install failed: {}", e),
+        progress => tracing::debug!("{:?}", progress),
+    });
+    ((id, progress), state)
+}
+
+async fn step(id: String, state: State) -> ((String, Progress), State) {
     match state {
-        State::Ready(checksum_url, tarball_url) => {
-            let response = reqwest::get(&checksum_url).await;
+        State::ReadyLocal { path, target } => {
+            let size = match fs::metadata(&path) {
+                Ok(metadata) if metadata.is_file() => metadata.len(),
+                _ => {
+                    return (
+                        (id, Progress::Errored(DownloaderError::FilesystemError)),
+                        State::Finished,
+                    )
+                }
+            };
+            if let Err(e) = disk::require_space(&target, disk::estimate_unpacked_size(size)) {
+                return ((id, Progress::Errored(e)), State::Finished);
+            }
 
-            match response {
-                Ok(response) => {
-                    let content = response.text().await;
-                    if let Ok(checksum) = content {
-                        let checksum: Vec<&str> = checksum.split(' ').collect();
+            (
+                (id, Progress::CheckIntegrity),
+                State::CheckIntegrity {
+                    checksum: local_checksum(&path),
+                    tarball: Tarball::Local(path),
+                    cache: None,
+                    signature: None,
+                    target,
+                },
+            )
+        }
+        State::Ready {
+            checksum_url,
+            tarball_url,
+            tarball_size,
+            cache,
+            signing,
+            target,
+        } => {
+            let response = http::get(&checksum_url).await;
 
-                        let checksum = checksum[0];
+            let checksum = match response {
+                Ok(response) => match response.text().await {
+                    Ok(checksum) => checksum.split(' ').next().unwrap_or("").to_string(),
+                    Err(_) => {
                         return (
-                            (id, Progress::Started),
-                            State::TarballDownloadStarting {
-                                tarball_url,
-                                checksum: checksum.to_string(),
-                            },
-                        );
-                    } else {
-                        ((id, Progress::Errored), State::Finished)
+                            (id, Progress::Errored(DownloaderError::DownloadError)),
+                            State::Finished,
+                        )
                     }
+                },
+                // Offline, a cached copy of the same asset can still be used.
+                // For signed sources it must have been verified when cached.
+                Err(_) => {
+                    let cached = cache
+                        .as_ref()
+                        .and_then(|slot| cache::lookup_name(&slot.name))
+                        .filter(|(_, entry)| signing.is_none() || entry.signed_by.is_some());
+                    return match cached {
+                        Some((path, entry)) => {
+                            let signature = entry.signed_by.map(SignatureCheck::Verified);
+                            from_cache(id, path, entry.hash, signature, target)
+                        }
+                        None => (
+                            (id, Progress::Errored(DownloaderError::NetworkError)),
+                            State::Finished,
+                        ),
+                    };
                 }
-                Err(_) => ((id, Progress::Errored), State::Finished),
+            };
+
+            let signature = match signing {
+                Some(signing) => match fetch_signature(signing).await {
+                    Ok(signature) => Some(signature),
+                    Err(e) => return ((id, Progress::Errored(e)), State::Finished),
+                },
+                None => None,
+            };
+
+            if let Some(path) = cache.as_ref().and_then(|_| cache::lookup(&checksum)) {
+                return from_cache(id, path, checksum, signature, target);
             }
+
+            if let Err(e) = disk::check_install_space(&staging_dir(), &target, tarball_size) {
+                return ((id, Progress::Errored(e)), State::Finished);
+            }
+
+            (
+                (id, Progress::Started),
+                State::TarballDownloadStarting {
+                    tarball_url,
+                    checksum,
+                    cache,
+                    signature,
+                    target,
+                },
+            )
         }
         State::TarballDownloadStarting {
             tarball_url,
             checksum,
+            cache,
+            signature,
+            target,
         } => {
-            let response = reqwest::get(&tarball_url).await;
+            let response = http::get(&tarball_url).await;
 
-            match response {
-                Ok(response) => {
-                    if let Some(total) = response.content_length() {
-                        (
-                            (id, Progress::Started),
-                            State::TarballDownloading {
-                                response,
-                                total,
-                                downloaded: 0,
-                                checksum,
-                                tarball: BytesMut::new(),
-                            },
-                        )
-                    } else {
-                        ((id, Progress::Errored), State::Finished)
-                    }
+            let tarball = match tempfile::Builder::new()
+                .prefix("proton_downloader")
+                .tempfile_in(staging_dir())
+            {
+                Ok(file) => file,
+                Err(_) => {
+                    return (
+                        (id, Progress::Errored(DownloaderError::FilesystemError)),
+                        State::Finished,
+                    )
                 }
-                Err(_) => ((id, Progress::Errored), State::Finished),
+            };
+
+            match response {
+                // Without a Content-Length the download is shown as
+                // indeterminate, the checksum still catches truncation.
+                Ok(response) => (
+                    (id, Progress::Started),
+                    State::TarballDownloading(Box::new(Downloading {
+                        total: response.content_length(),
+                        response,
+                        downloaded: 0,
+                        meter: RateMeter::new(),
+                        pacer: Pacer::new(),
+                        checksum,
+                        tarball,
+                        cache,
+                        signature,
+                        target,
+                    })),
+                ),
+                Err(_) => (
+                    (id, Progress::Errored(DownloaderError::NetworkError)),
+                    State::Finished,
+                ),
             }
         }
-        State::TarballDownloading {
-            mut response,
-            total,
-            downloaded,
-            checksum,
-            mut tarball,
-        } => match response.chunk().await {
+        State::TarballDownloading(mut download) => match download.response.chunk().await {
             Ok(Some(chunk)) => {
-                tarball.put(chunk);
-                let percentage = (tarball.len() as f32 / total as f32) * 100.0;
+                if download.tarball.write_all(&chunk).is_err() {
+                    return (
+                        (id, Progress::Errored(DownloaderError::FilesystemError)),
+                        State::Finished,
+                    );
+                }
+                ratelimit::throttle(&id, &mut download.pacer, chunk.len() as u64).await;
+                download.downloaded += chunk.len() as u64;
+                let transfer = Transfer {
+                    downloaded: download.downloaded,
+                    total: download.total,
+                    rate: download.meter.update(download.downloaded),
+                };
 
                 (
-                    (id, Progress::Advanced(percentage)),
-                    State::TarballDownloading {
-                        response,
-                        tarball,
-                        total,
-                        downloaded,
-                        checksum,
+                    (id, Progress::Advanced(transfer)),
+                    State::TarballDownloading(download),
+                )
+            }
+            Ok(None) => {
+                let Downloading {
+                    checksum,
+                    tarball,
+                    cache,
+                    signature,
+                    target,
+                    ..
+                } = *download;
+                (
+                    (id, Progress::CheckIntegrity),
+                    State::CheckIntegrity {
+                        checksum: Some(checksum),
+                        tarball: Tarball::Downloaded(tarball),
+                        cache,
+                        signature,
+                        target,
                     },
                 )
             }
-            Ok(None) => (
-                (id, Progress::CheckIntegrity),
-                State::CheckIntegrity { checksum, tarball },
+            Err(_) => (
+                (id, Progress::Errored(DownloaderError::NetworkError)),
+                State::Finished,
             ),
-            Err(_) => ((id, Progress::Errored), State::Finished),
         },
-        State::CheckIntegrity { checksum, tarball } => {
-            let mut hasher = Sha512::new();
+        State::CheckIntegrity {
+            checksum,
+            tarball,
+            cache,
+            signature,
+            target,
+        } => {
+            // A local tarball without a checksum file is trusted as it is.
+            let checksum = match checksum {
+                Some(checksum) => checksum,
+                None => {
+                    return (
+                        (id, Progress::Installing),
+                        State::Install {
+                            tarball,
+                            checksum: None,
+                            target,
+                        },
+                    )
+                }
+            };
 
-            hasher.update(&tarball);
+            let checked = blocking(move || {
+                let signed_by = check_tarball(&tarball, &checksum, signature, cache)?;
+                Ok((tarball, checksum, signed_by))
+            })
+            .await;
+            match checked {
+                Ok((tarball, checksum, signed_by)) => {
+                    let progress = match signed_by {
+                        Some(name) => Progress::Signed(name),
+                        None => Progress::Installing,
+                    };
+                    (
+                        (id, progress),
+                        State::Install {
+                            tarball,
+                            checksum: Some(checksum),
+                            target,
+                        },
+                    )
+                }
+                Err(e) => ((id, Progress::Errored(e)), State::Finished),
+            }
+        }
+        State::Install {
+            tarball,
+            checksum,
+            target,
+        } => match blocking(move || extract(&tarball, &target, checksum.as_deref())).await {
+            Ok(_) => ((id, Progress::Finished), State::Finished),
+            Err(e) => ((id, Progress::Errored(e)), State::Finished),
+        },
+        State::Finished => iced::futures::future::pending().await,
+    }
+}
 
-            let result = hasher.finalize();
-            let mut caluclated_checksum = String::new();
+/// Compares the tarball with its checksum, checks the signature and stores
+/// it in the cache, returning who signed it.
+fn check_tarball(
+    tarball: &Tarball,
+    checksum: &str,
+    signature: Option<SignatureCheck>,
+    cache: Option<CacheSlot>,
+) -> Result<Option<String>, DownloaderError> {
+    let calculated =
+        manifest::hash_file(tarball.path()).or(Err(DownloaderError::FilesystemError))?;
+    if calculated != checksum {
+        // Drop a damaged cached copy so the next attempt downloads it.
+        if let Tarball::Cached(_) = tarball {
+            let _ = cache::remove(checksum);
+        }
+        return Err(DownloaderError::ChecksumMismatch);
+    }
 
-            for byte in result {
-                caluclated_checksum.push_str(format!("{:02x}", byte).as_str());
-            }
+    let signed_by = match signature {
+        Some(SignatureCheck::Pending { kind, data, keys }) => {
+            Some(signature::verify(tarball.path(), kind, &data, &keys)?)
+        }
+        Some(SignatureCheck::Verified(name)) => Some(name),
+        None => None,
+    };
 
-            if caluclated_checksum.eq(&checksum) {
-                ((id, Progress::Installing), State::Install { tarball })
-            } else {
-                ((id, Progress::Errored), State::Finished)
-            }
+    // Failing to cache only costs a download next time.
+    if let Some(slot) = cache {
+        if let Err(e) = cache::store(
+            checksum,
+            &slot.name,
+            tarball.path(),
+            slot.limit,
+            signed_by.as_deref(),
+        ) {
+            tracing::warn!("failed to cache {}: {}", slot.name, e);
         }
-        State::Install { tarball } => {
-            let tar = GzDecoder::new(tarball.clone().reader());
-            let mut archive = Archive::new(tar);
-            match archive.unpack(".") {
-                Ok(_) => ((id, Progress::Finished), State::Finished),
-                Err(_) => ((id, Progress::Errored), State::Finished),
+    }
+    Ok(signed_by)
+}
+
+fn is_xz(path: &Path) -> bool {
+    let mut magic = [0u8; 6];
+    match File::open(path).and_then(|mut file| file.read_exact(&mut magic)) {
+        Ok(_) => magic == XZ_MAGIC,
+        Err(_) => false,
+    }
+}
+
+/// GE-Proton ships `.tar.gz` while Wine-GE ships `.tar.xz`, the format is
+/// told apart by the file header rather than the asset name.
+fn open_archive(path: &Path) -> Result<Archive<Box<dyn Read>>, DownloaderError> {
+    let file = File::open(path).or(Err(DownloaderError::FilesystemError))?;
+    let reader: Box<dyn Read> = if is_xz(path) {
+        Box::new(XzDecoder::new(BufReader::new(file)))
+    } else {
+        Box::new(GzDecoder::new(BufReader::new(file)))
+    };
+    Ok(Archive::new(reader))
+}
+
+fn unpacked_size(path: &Path) -> Result<u64, DownloaderError> {
+    if is_xz(path) {
+        let compressed = fs::metadata(path)
+            .or(Err(DownloaderError::FilesystemError))?
+            .len();
+        Ok(disk::estimate_unpacked_size(compressed))
+    } else {
+        disk::gzip_unpacked_size(path).or(Err(DownloaderError::FilesystemError))
+    }
+}
+
+/// Unpacks entry by entry with `unpack_in`, which refuses absolute paths,
+/// `..` components and writes through symlinks leading out of `target`.
+/// Afterwards the extracted files are recorded in a manifest.
+fn extract(
+    tarball: &Tarball,
+    target: &Path,
+    checksum: Option<&str>,
+) -> Result<(), DownloaderError> {
+    disk::require_space(target, unpacked_size(tarball.path())?)?;
+
+    fs::create_dir_all(target).or(Err(DownloaderError::FilesystemError))?;
+
+    let mut archive = open_archive(tarball.path())?;
+    let entries = archive
+        .entries()
+        .or(Err(DownloaderError::FilesystemError))?;
+    let mut paths = Vec::new();
+    for entry in entries {
+        let mut entry = entry.or(Err(DownloaderError::FilesystemError))?;
+        paths.push(
+            entry
+                .path()
+                .or(Err(DownloaderError::FilesystemError))?
+                .into_owned(),
+        );
+        let unpacked = entry
+            .unpack_in(target)
+            .or(Err(DownloaderError::FilesystemError))?;
+        if !unpacked {
+            return Err(DownloaderError::UnsafeArchive);
+        }
+    }
+
+    manifest::record(target, &paths, checksum).or(Err(DownloaderError::FilesystemError))
+}
+
+/// Where to download the tarball of a tool that is no longer cached.
+pub struct RepairSource {
+    tarball_url: String,
+    checksum_url: String,
+    tarball_size: u64,
+    signing: Option<Signing>,
+}
+
+impl RepairSource {
+    /// When `keys` is not empty the download has to be signed by one of them,
+    /// as for [`install`].
+    pub fn new(release: &Proton, keys: Vec<TrustedKey>) -> RepairSource {
+        RepairSource {
+            tarball_url: release.get_tarball_url(),
+            checksum_url: release.get_checksum_url(),
+            tarball_size: release.get_tarball_size(),
+            signing: signing(release, keys),
+        }
+    }
+}
+
+/// Runs hashing and unpacking off the async executor.
+async fn blocking<T, F>(work: F) -> Result<T, DownloaderError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, DownloaderError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .or(Err(DownloaderError::FilesystemError))?
+}
+
+/// Re-extracts the missing and modified files of an installed tool from the
+/// tarball it came from, leaving everything else alone, and verifies the
+/// folder again afterwards.
+pub async fn repair(
+    tool: PathBuf,
+    download: Option<RepairSource>,
+    cache_limit: u64,
+) -> Result<Report, DownloaderError> {
+    let verified = tool.clone();
+    let report = blocking(move || manifest::verify(&verified)).await?;
+    if report.missing.is_empty() && report.modified.is_empty() {
+        return Ok(report);
+    }
+
+    let target = tool
+        .parent()
+        .ok_or(DownloaderError::FilesystemError)?
+        .to_path_buf();
+    let expected = manifest::tarball_hash(&tool);
+    let cached = match expected.clone() {
+        Some(hash) => {
+            blocking(move || {
+                let path = cache::lookup(&hash);
+                Ok(path.filter(|path| manifest::hash_file(path).ok() == Some(hash)))
+            })
+            .await?
+        }
+        None => None,
+    };
+    let tarball = match cached {
+        Some(path) => Tarball::Cached(path),
+        None => {
+            let download = download.ok_or(DownloaderError::RepairUnavailable)?;
+            redownload(download, expected, &target, cache_limit).await?
+        }
+    };
+
+    let folder = tool.file_name().ok_or(DownloaderError::FilesystemError)?;
+    let wanted: HashSet<PathBuf> = report
+        .missing
+        .iter()
+        .chain(&report.modified)
+        .map(|path| Path::new(folder).join(path))
+        .collect();
+    blocking(move || {
+        restore(&tarball, &target, &wanted)?;
+        manifest::verify(&tool)
+    })
+    .await
+}
+
+/// Downloads the tarball again with the same checks as an install: free
+/// space first, then the checksum and, for signed sources, the signature.
+async fn redownload(
+    download: RepairSource,
+    expected: Option<String>,
+    target: &Path,
+    cache_limit: u64,
+) -> Result<Tarball, DownloaderError> {
+    let checksum = match expected {
+        Some(checksum) => checksum,
+        None => http::get(&download.checksum_url)
+            .await
+            .or(Err(DownloaderError::NetworkError))?
+            .text()
+            .await
+            .or(Err(DownloaderError::DownloadError))?
+            .split(' ')
+            .next()
+            .unwrap_or("")
+            .to_string(),
+    };
+    let signature = match download.signing {
+        Some(signing) => Some(fetch_signature(signing).await?),
+        None => None,
+    };
+    disk::check_install_space(&staging_dir(), target, download.tarball_size)?;
+
+    let tarball = Tarball::Downloaded(download_to_staging(&download.tarball_url).await?);
+    let cache = cache_slot(&download.tarball_url, cache_limit);
+    blocking(move || {
+        check_tarball(&tarball, &checksum, signature, cache)?;
+        Ok(tarball)
+    })
+    .await
+}
+
+async fn download_to_staging(url: &str) -> Result<NamedTempFile, DownloaderError> {
+    let mut response = http::get(url)
+        .await
+        .or(Err(DownloaderError::NetworkError))?;
+    let mut file = tempfile::Builder::new()
+        .prefix("proton_downloader")
+        .tempfile_in(staging_dir())
+        .or(Err(DownloaderError::FilesystemError))?;
+    let mut pacer = Pacer::new();
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .or(Err(DownloaderError::NetworkError))?
+    {
+        file.write_all(&chunk)
+            .or(Err(DownloaderError::FilesystemError))?;
+        ratelimit::throttle(url, &mut pacer, chunk.len() as u64).await;
+    }
+    Ok(file)
+}
+
+/// Unpacks only the entries in `wanted`, replacing whatever is in their way.
+fn restore(
+    tarball: &Tarball,
+    target: &Path,
+    wanted: &HashSet<PathBuf>,
+) -> Result<(), DownloaderError> {
+    let mut archive = open_archive(tarball.path())?;
+    let entries = archive
+        .entries()
+        .or(Err(DownloaderError::FilesystemError))?;
+    for entry in entries {
+        let mut entry = entry.or(Err(DownloaderError::FilesystemError))?;
+        let path = manifest::normalize(&entry.path().or(Err(DownloaderError::FilesystemError))?);
+        if !wanted.contains(&path) {
+            continue;
+        }
+
+        let existing = target.join(&path);
+        if let Ok(metadata) = existing.symlink_metadata() {
+            let removed = match (entry.header().entry_type().is_dir(), metadata.is_dir()) {
+                (true, true) => Ok(()),
+                (false, true) => fs::remove_dir_all(&existing),
+                (_, false) => fs::remove_file(&existing),
+            };
+            removed.or(Err(DownloaderError::FilesystemError))?;
+        }
+
+        let unpacked = entry
+            .unpack_in(target)
+            .or(Err(DownloaderError::FilesystemError))?;
+        if !unpacked {
+            return Err(DownloaderError::UnsafeArchive);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transfer {
+    pub downloaded: u64,
+    /// Unknown when the server sends no Content-Length.
+    pub total: Option<u64>,
+    /// Smoothed bytes per second.
+    pub rate: f64,
+}
+
+impl Transfer {
+    pub fn percentage(&self) -> Option<f32> {
+        self.total
+            .filter(|total| *total > 0)
+            .map(|total| self.downloaded as f32 / total as f32 * 100.0)
+    }
+
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.total?.saturating_sub(self.downloaded);
+        if self.rate < 1.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(remaining as f64 / self.rate))
+    }
+}
+
+/// Transfer rate as an exponential moving average over samples of at least
+/// `SAMPLE`, so single chunks don't make the number jump around.
+pub struct RateMeter {
+    started: Instant,
+    sampled_at: Instant,
+    sampled_bytes: u64,
+    rate: Option<f64>,
+}
+
+impl RateMeter {
+    const SAMPLE: Duration = Duration::from_millis(500);
+    const SMOOTHING: f64 = 0.3;
+
+    pub fn new() -> Self {
+        let now = Instant::now();
+        RateMeter {
+            started: now,
+            sampled_at: now,
+            sampled_bytes: 0,
+            rate: None,
+        }
+    }
+
+    /// Takes the running byte count and returns the current estimate.
+    pub fn update(&mut self, downloaded: u64) -> f64 {
+        let now = Instant::now();
+        let elapsed = now - self.sampled_at;
+        if elapsed >= Self::SAMPLE {
+            let sample = (downloaded - self.sampled_bytes) as f64 / elapsed.as_secs_f64();
+            self.rate = Some(match self.rate {
+                Some(rate) => rate + Self::SMOOTHING * (sample - rate),
+                None => sample,
+            });
+            self.sampled_at = now;
+            self.sampled_bytes = downloaded;
+        }
+
+        match self.rate {
+            Some(rate) => rate,
+            // Until the first full sample, the average since the start.
+            None => {
+                let elapsed = (now - self.started).as_secs_f64();
+                if elapsed > 0.0 {
+                    downloaded as f64 / elapsed
+                } else {
+                    0.0
+                }
             }
         }
-        State::Finished => todo!(),
     }
 }
 
+impl Default for RateMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=59 => tr!("duration-seconds", seconds = secs),
+        60..=3599 => tr!("duration-minutes", minutes = secs / 60, seconds = secs % 60),
+        _ => tr!(
+            "duration-hours",
+            hours = secs / 3600,
+            minutes = secs % 3600 / 60
+        ),
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum Progress {
     Started,
-    Advanced(f32),
+    Advanced(Transfer),
     CheckIntegrity,
+    /// Verified a signature by the named key, extraction starts next.
+    Signed(String),
     Installing,
     Finished,
-    Errored,
+    Errored(DownloaderError),
+}
+
+pub enum Tarball {
+    Downloaded(NamedTempFile),
+    Local(PathBuf),
+    Cached(PathBuf),
+}
+
+impl Tarball {
+    pub fn path(&self) -> &Path {
+        match self {
+            Tarball::Downloaded(file) => file.path(),
+            Tarball::Local(path) | Tarball::Cached(path) => path,
+        }
+    }
+}
+
+/// Signature asset of a release and the keys allowed to have made it.
+pub struct Signing {
+    url: Option<String>,
+    keys: Vec<TrustedKey>,
+}
+
+pub enum SignatureCheck {
+    /// Fetched with the release, checked once the checksum matches.
+    Pending {
+        kind: SignatureKind,
+        data: Vec<u8>,
+        keys: Vec<TrustedKey>,
+    },
+    /// Checked before the tarball went into the cache.
+    Verified(String),
+}
+
+/// Where a downloaded tarball goes in the cache once it is verified.
+pub struct CacheSlot {
+    name: String,
+    limit: u64,
+}
+
+/// A tarball download in progress, written to `tarball` chunk by chunk.
+pub struct Downloading {
+    response: reqwest::Response,
+    tarball: NamedTempFile,
+    total: Option<u64>,
+    downloaded: u64,
+    meter: RateMeter,
+    pacer: Pacer,
+    checksum: String,
+    cache: Option<CacheSlot>,
+    signature: Option<SignatureCheck>,
+    target: PathBuf,
 }
 
 pub enum State {
-    Ready(String, String),
-    TarballDownloadStarting {
+    ReadyLocal {
+        path: PathBuf,
+        target: PathBuf,
+    },
+    Ready {
+        checksum_url: String,
         tarball_url: String,
-        checksum: String,
+        tarball_size: u64,
+        cache: Option<CacheSlot>,
+        signing: Option<Signing>,
+        target: PathBuf,
     },
-    TarballDownloading {
-        response: reqwest::Response,
-        tarball: BytesMut,
-        total: u64,
-        downloaded: u64,
+    TarballDownloadStarting {
+        tarball_url: String,
         checksum: String,
+        cache: Option<CacheSlot>,
+        signature: Option<SignatureCheck>,
+        target: PathBuf,
     },
+    /// Boxed, it is much larger than the other steps.
+    TarballDownloading(Box<Downloading>),
     CheckIntegrity {
-        checksum: String,
-        tarball: BytesMut,
+        checksum: Option<String>,
+        tarball: Tarball,
+        cache: Option<CacheSlot>,
+        signature: Option<SignatureCheck>,
+        target: PathBuf,
     },
     Install {
-        tarball: BytesMut,
+        tarball: Tarball,
+        /// SHA-512 of the tarball, recorded in the manifest for repairs.
+        checksum: Option<String>,
+        target: PathBuf,
     },
     Finished,
 }
@@ -0,0 +1,191 @@
+use crate::i18n;
+use crate::source::Source;
+use crate::vdf;
+use crate::version::Version;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct InstalledTool {
+    pub folder: String,
+    pub path: PathBuf,
+    /// Name Steam uses to refer to the tool, from `compatibilitytool.vdf`.
+    pub internal_name: Option<String>,
+    pub display_name: Option<String>,
+    /// Release name from the `version` file shipped with GE builds.
+    pub version: Option<String>,
+    /// `None` when the directory could not be read.
+    pub size: Option<u64>,
+    /// Whether the launcher owning the directory would pick the build up.
+    pub valid: bool,
+}
+
+impl InstalledTool {
+    pub fn name(&self) -> &str {
+        self.internal_name.as_deref().unwrap_or(&self.folder)
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(self.name())
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    /// Taken from the `version` file when it has one, otherwise from the
+    /// names the build goes by.
+    pub fn parsed_version(&self) -> Option<Version> {
+        self.version
+            .as_deref()
+            .and_then(Version::parse)
+            .or_else(|| Version::parse(self.name()))
+            .or_else(|| Version::parse(&self.folder))
+    }
+
+    /// Wine-GE folders wrap the release tag, e.g. `lutris-GE-Proton8-26-x86_64`
+    /// for `GE-Proton8-26`, so the tag may also appear inside the folder name
+    /// as long as it is not followed by more digits of a longer tag.
+    pub fn matches(&self, tag: &str) -> bool {
+        if self.folder == tag
+            || self.internal_name.as_deref() == Some(tag)
+            || self.version.as_deref() == Some(tag)
+        {
+            return true;
+        }
+
+        self.folder.match_indices(tag).any(|(index, _)| {
+            let before = self.folder[..index].chars().next_back();
+            let after = self.folder[index + tag.len()..].chars().next();
+            !matches!(before, Some(c) if c.is_ascii_alphanumeric())
+                && !matches!(after, Some(c) if c.is_ascii_digit())
+        })
+    }
+}
+
+/// Never fails: a folder whose name is not UTF-8 or that can't be read in
+/// full is returned as broken, with no size, instead of hiding the others.
+pub fn inspect(path: &Path) -> InstalledTool {
+    let name = path.file_name().unwrap_or_default();
+    let folder = name.to_string_lossy().into_owned();
+    let size = match name.to_str() {
+        Some(_) => disk_usage(path)
+            .map_err(|e| tracing::warn!("failed to read {}: {}", path.display(), e))
+            .ok(),
+        None => None,
+    };
+
+    let (internal_name, display_name) = match read_compatibility_tool(path) {
+        Some((internal_name, display_name)) => (Some(internal_name), display_name),
+        None => (None, None),
+    };
+
+    InstalledTool {
+        folder,
+        path: path.to_path_buf(),
+        valid: size.is_some() && internal_name.is_some(),
+        internal_name,
+        display_name,
+        version: read_version(path),
+        size,
+    }
+}
+
+fn read_compatibility_tool(path: &Path) -> Option<(String, Option<String>)> {
+    let manifest = vdf::read_text_file(&path.join("compatibilitytool.vdf")).ok()?;
+    let tools = manifest.path(&["compatibilitytools", "compat_tools"])?;
+    let tool = tools.entries().next()?;
+    let display_name = tool
+        .value
+        .as_section()
+        .and_then(|section| section.get_str("display_name"))
+        .map(String::from);
+
+    Some((tool.key.clone(), display_name))
+}
+
+/// The `version` file holds a build timestamp followed by the release name,
+/// e.g. `1697643421 GE-Proton8-21`.
+fn read_version(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path.join("version")).ok()?;
+    let mut parts = content.split_whitespace();
+    let first = parts.next()?;
+    match parts.next() {
+        Some(name) if first.chars().all(|c| c.is_ascii_digit()) => Some(name.to_string()),
+        _ => Some(first.to_string()),
+    }
+}
+
+/// Space actually allocated on disk, counting hard linked files once and not
+/// following symlinks out of the tool directory.
+pub fn disk_usage(path: &Path) -> io::Result<u64> {
+    let mut seen = HashSet::new();
+    let mut total = 0;
+    let mut pending = vec![path.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let metadata = entry.path().symlink_metadata()?;
+
+            if metadata.nlink() > 1 && !seen.insert((metadata.dev(), metadata.ino())) {
+                continue;
+            }
+            total += metadata.blocks() * 512;
+
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            }
+        }
+    }
+
+    Ok(total)
+}
+
+/// The oldest builds of `source` beyond the newest `keep`, judged by their
+/// version and, for names that don't parse, by when their folder was
+/// written. Tools that Steam has mapped to a game are never picked. Nothing
+/// is deleted here, the caller asks first.
+pub fn prune_candidates(
+    tools: &[InstalledTool],
+    source: &Source,
+    keep: usize,
+    in_use: &HashSet<String>,
+) -> Vec<InstalledTool> {
+    let mut owned: Vec<(&InstalledTool, Option<Version>, i64)> = tools
+        .iter()
+        .filter(|tool| source.owns(&tool.folder) || source.owns(tool.name()))
+        .map(|tool| {
+            let modified = fs::metadata(&tool.path).map(|m| m.mtime()).unwrap_or(0);
+            (tool, tool.parsed_version(), modified)
+        })
+        .collect();
+    // Option orders None first, so reversing puts unparsed names last.
+    owned.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)));
+
+    owned
+        .into_iter()
+        .skip(keep)
+        .map(|(tool, _, _)| tool)
+        .filter(|tool| !in_use.contains(tool.name()))
+        .cloned()
+        .collect()
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["size-bytes", "size-kib", "size-mib", "size-gib", "size-tib"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        tr!(UNITS[0], size = bytes)
+    } else {
+        tr!(UNITS[unit], size = i18n::format_decimal(size, 1))
+    }
+}
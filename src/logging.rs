@@ -0,0 +1,203 @@
+use crate::paths;
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Mutex;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt as format, EnvFilter};
+
+/// Used when `RUST_LOG` is not set.
+const DEFAULT_FILTER: &str = "warn,proton_downloader=info";
+const FILE_PREFIX: &str = "proton_downloader";
+/// Daily files kept in the log directory.
+const KEEP_FILES: usize = 7;
+/// Records kept in memory for the log panel.
+const KEEP_RECORDS: usize = 1000;
+
+static RECORDS: Mutex<VecDeque<Record>> = Mutex::new(VecDeque::new());
+
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    /// Install job the record was logged in, if any.
+    pub job: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:>5} ", self.time.format("%H:%M:%S"), self.level)?;
+        if let Some(job) = &self.job {
+            write!(f, "[{}] ", job)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobFilter {
+    All,
+    Job(String),
+}
+
+impl JobFilter {
+    fn matches(&self, record: &Record) -> bool {
+        match self {
+            JobFilter::All => true,
+            JobFilter::Job(job) => record.job.as_ref() == Some(job),
+        }
+    }
+}
+
+impl fmt::Display for JobFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobFilter::All => write!(f, "{}", tr!("log-all-jobs")),
+            JobFilter::Job(job) => write!(f, "{}", job),
+        }
+    }
+}
+
+/// Levels offered in the log panel, each shows itself and the more severe.
+pub const LEVELS: [Level; 4] = [Level::ERROR, Level::WARN, Level::INFO, Level::DEBUG];
+
+/// Logs to stderr and to a daily rotated file in the state directory, and
+/// keeps the latest records for the log panel. The returned guard flushes
+/// the file when dropped, so it has to live until the program exits.
+pub fn init() -> Option<WorkerGuard> {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let (file, guard) = match file_appender() {
+        Some(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = format::layer().with_writer(writer).with_ansi(false);
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(format::layer().with_writer(std::io::stderr))
+        .with(file)
+        .with(Panel);
+    if registry.try_init().is_err() {
+        return None;
+    }
+    if guard.is_none() {
+        tracing::warn!("logging to stderr only, the log directory is not writable");
+    }
+    guard
+}
+
+fn file_appender() -> Option<RollingFileAppender> {
+    let dir = paths::log_dir()?;
+    std::fs::create_dir_all(&dir).ok()?;
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(KEEP_FILES)
+        .build(dir)
+        .ok()
+}
+
+/// Records at `level` or more severe that belong to the filtered jobs,
+/// oldest first.
+pub fn records(level: Level, job: &JobFilter) -> Vec<Record> {
+    let records = RECORDS.lock().unwrap_or_else(|e| e.into_inner());
+    records
+        .iter()
+        .filter(|record| record.level <= level && job.matches(record))
+        .cloned()
+        .collect()
+}
+
+/// Jobs with at least one kept record, for the panel's job filter.
+pub fn jobs() -> Vec<JobFilter> {
+    let records = RECORDS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut jobs: Vec<String> = records
+        .iter()
+        .filter_map(|record| record.job.clone())
+        .collect();
+    jobs.sort();
+    jobs.dedup();
+    std::iter::once(JobFilter::All)
+        .chain(jobs.into_iter().map(JobFilter::Job))
+        .collect()
+}
+
+/// Job name stored on spans with a `job` field.
+struct Job(String);
+
+/// Collects the message and the other fields of an event into one line.
+#[derive(Default)]
+struct Line {
+    job: Option<String>,
+    message: String,
+    fields: String,
+}
+
+impl Visit for Line {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "job" => self.job = Some(value.to_string()),
+            "message" => self.message = value.to_string(),
+            name => self.fields.push_str(&format!(" {}={}", name, value)),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "job" => self.job = Some(format!("{:?}", value)),
+            "message" => self.message = format!("{:?}", value),
+            name => self.fields.push_str(&format!(" {}={:?}", name, value)),
+        }
+    }
+}
+
+/// Layer feeding [`RECORDS`].
+struct Panel;
+
+impl<S> Layer<S> for Panel
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attributes: &Attributes<'_>, id: &Id, context: Context<'_, S>) {
+        let mut line = Line::default();
+        attributes.record(&mut line);
+        if let (Some(job), Some(span)) = (line.job, context.span(id)) {
+            span.extensions_mut().insert(Job(job));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, context: Context<'_, S>) {
+        let mut line = Line::default();
+        event.record(&mut line);
+        let job = line.job.or_else(|| {
+            context.event_scope(event)?.find_map(|span| {
+                let extensions = span.extensions();
+                extensions.get::<Job>().map(|job| job.0.clone())
+            })
+        });
+
+        let mut records = RECORDS.lock().unwrap_or_else(|e| e.into_inner());
+        if records.len() == KEEP_RECORDS {
+            records.pop_front();
+        }
+        records.push_back(Record {
+            time: Local::now(),
+            level: *event.metadata().level(),
+            job,
+            message: line.message + &line.fields,
+        });
+    }
+}
@@ -1,69 +1,355 @@
+#[macro_use]
+pub mod i18n;
+pub mod cache;
+pub mod check;
+pub mod cli;
+pub mod disk;
+pub mod http;
 pub mod install;
+pub mod installed;
+pub mod logging;
+pub mod manifest;
+pub mod notify;
+pub mod palette;
 //pub mod installer;
+pub mod paths;
 pub mod proton;
+pub mod proton_widget;
+pub mod ratelimit;
+pub mod settings;
+pub mod signature;
+pub mod source;
+pub mod steam;
+pub mod target;
+pub mod vdf;
+pub mod version;
+use cache::CacheEntry;
 use flate2::read::GzDecoder;
+use i18n::LanguageChoice;
 use iced::executor;
 use iced::futures::StreamExt;
 use iced::widget::{
-    button, column, container, horizontal_space, row, text, vertical_space, Button, Column, Row,
-    Text,
+    button, checkbox, column, container, horizontal_space, pick_list, progress_bar, row,
+    scrollable, slider, text, text_input, vertical_space, Button, Column, Row, Text,
 };
-use iced::{Application, Command, Element, Length, Settings, Theme};
+use iced::{event, keyboard, theme, window, Event};
+use iced::{Application, Command, Element, Length, Settings, Subscription, Theme};
+use install::Progress;
+use installed::{format_size, InstalledTool};
+use logging::JobFilter;
+use manifest::Report;
+use notify::{Notification, Notifier};
+use octocrab::checks;
 use octocrab::models::repos::Release;
-use octocrab::{checks, Octocrab};
-use proton::Proton;
+use palette::{Action, Palette};
+use proton::{proton_status, Proton};
+use proton_widget::proton_widget;
+use ratelimit::Rate;
 use regex::Regex;
+use settings::{Config, ReleaseFilter, ReleaseSort, ThemeChoice, UpdatePolicy};
 use sha2::{Digest, Sha512};
-use std::collections::HashMap;
+use source::{Channel, Source};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs::File;
 use std::io::{copy, BufReader};
-use std::str::FromStr;
+use std::path::PathBuf;
+use std::time::Duration;
 use tar::Archive;
+use target::{Destination, TargetKind};
 use tempfile::Builder;
 use tokio::io;
 use tokio_stream::wrappers::ReadDirStream;
+use tracing::Level;
 
 struct Downloader {
-    content: Vec<Release>,
+    config: Config,
+    screen: Screen,
+    draft: Config,
+    new_destination_name: String,
+    new_destination_kind: TargetKind,
+    new_destination_path: String,
+    draft_proxy: String,
+    draft_no_proxy: String,
+    content: Vec<(&'static Source, Release)>,
+    destination: Option<Destination>,
     status: String,
     proton_list: HashMap<String, Proton>,
+    installed: Vec<InstalledTool>,
+    /// Tools Steam has mapped to a game or as the default.
+    in_use: HashSet<String>,
+    installing: HashMap<String, Job>,
+    /// Key of the release card last clicked.
+    selected: Option<String>,
+    queued: VecDeque<(String, Destination)>,
+    pending_steam_action: Option<SteamAction>,
+    steam_prompt: bool,
+    pending_removal: Option<Removal>,
+    cache_entries: Vec<CacheEntry>,
+    rate_limit: Rate,
+    verify_reports: HashMap<PathBuf, Result<Report, DownloaderError>>,
+    notifier: Option<Notifier>,
+    /// Updates already announced in a notification.
+    notified: HashSet<String>,
+    log_level: Level,
+    log_job: JobFilter,
+    log_records: Vec<logging::Record>,
+    log_jobs: Vec<JobFilter>,
+    palette: Option<Palette>,
+    /// Counts edits of the search text, only the last one gets saved.
+    search_edits: u64,
+}
+
+/// Installed tools waiting for the user to confirm their deletion.
+struct Removal {
+    destination: Destination,
+    tools: Vec<InstalledTool>,
+}
+
+impl Removal {
+    fn names(&self) -> String {
+        let names: Vec<&str> = self.tools.iter().map(|tool| tool.display_name()).collect();
+        names.join(", ")
+    }
+}
+
+struct Job {
+    destination: Destination,
+    progress: Progress,
+    /// Set for tarballs installed from disk instead of a release download.
+    file: Option<PathBuf>,
+    rate: Rate,
+}
+
+/// Prefix of the notification action that installs the release keyed by
+/// the rest of the action key.
+const INSTALL_ACTION: &str = "install:";
+/// Entries shown in the command palette at most.
+const PALETTE_MATCHES: usize = 8;
+/// Pause in typing after which the search text is saved.
+const SEARCH_SAVE_DELAY: Duration = Duration::from_millis(800);
+
+fn search_input() -> text_input::Id {
+    text_input::Id::new("search")
+}
+
+fn palette_input() -> text_input::Id {
+    text_input::Id::new("palette")
+}
+
+fn release_list() -> scrollable::Id {
+    scrollable::Id::new("releases")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Screen {
+    Releases,
+    Settings,
+    Cache,
+    Log,
+}
+
+#[derive(Debug, Clone)]
+enum SteamAction {
+    /// Steam root whose config to edit, and the tool name.
+    SetDefaultTool(PathBuf, String),
 }
 
 fn main() -> iced::Result {
-    //println!("Hello, world!");
-    Downloader::run(Settings {
-        ..Settings::default()
-    })
+    let args = match cli::parse(std::env::args().skip(1)) {
+        cli::ParseResult::Run(args) => args,
+        cli::ParseResult::Help(usage) => {
+            println!("{}", usage);
+            return Ok(());
+        }
+        cli::ParseResult::Error(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+    let _log = logging::init();
+
+    let mut config = match settings::load() {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::warn!("using default settings: {}", e);
+            Config::default()
+        }
+    };
+
+    i18n::set_language(config.language.as_deref());
+
+    if config.destinations.is_empty() {
+        config.destinations = target::detect();
+    }
+
+    if let Err(e) = http::configure(&config) {
+        tracing::warn!("ignoring proxy settings: {}", e);
+    }
+
+    // The command line only overrides the limit for this run.
+    ratelimit::set_global(args.limit_rate.unwrap_or(Rate(config.bandwidth_limit)));
+
+    let result = match args.mode {
+        cli::Mode::Gui => return Downloader::run(Settings::with_flags(config)),
+        cli::Mode::Check => tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .or(Err(DownloaderError::FilesystemError))
+            .and_then(|runtime| runtime.block_on(check::run(config, args.force))),
+        cli::Mode::InstallTimer => check::install_timer().map(|dir| {
+            println!(
+                "{}",
+                tr!("timer-installed", path = dir.display().to_string())
+            );
+        }),
+        cli::Mode::RemoveTimer => check::remove_timer(),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    Ok(())
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Refresh,
-    ReleasesLoaded(Result<Vec<Release>, DownloaderError>),
-    FilesystemLoaded(Result<Vec<String>, DownloaderError>),
+    ReleasesLoaded(Result<Vec<(&'static Source, Release)>, DownloaderError>),
+    FilesystemLoaded(Result<Vec<InstalledTool>, DownloaderError>),
     Install(String),
+    CancelInstall(String),
+    SelectRelease(String),
+    RemoveRelease(String),
+    VerifyRelease(String),
     Installed(Result<(), DownloaderError>),
+    InstallProgress((String, Progress)),
+    InstallFromFile,
+    FilePicked(Option<PathBuf>),
+    FileDropped(PathBuf),
+    Pruned(Destination, Result<Vec<InstalledTool>, DownloaderError>),
+    PruneCandidates(
+        Destination,
+        Result<(Vec<InstalledTool>, Vec<InstalledTool>), DownloaderError>,
+    ),
+    ConfirmRemoval,
+    Removed(
+        String,
+        Destination,
+        Result<Vec<InstalledTool>, DownloaderError>,
+    ),
+    CancelRemoval,
+    SelectDestination(Destination),
+    SetDefaultTool(String),
+    VerifyTool(PathBuf),
+    ToolVerified(PathBuf, Result<Report, DownloaderError>),
+    RepairTool(PathBuf),
+    ToolRepaired(PathBuf, Result<Report, DownloaderError>),
+    SteamConfigUpdated(Result<(), DownloaderError>),
+    ShutdownSteam,
+    CancelSteamAction,
+    SteamExited(Result<(), DownloaderError>),
+    ShowSettings,
+    ShowReleases,
+    SettingsTheme(ThemeChoice),
+    SettingsLanguage(LanguageChoice),
+    SettingsDefaultDestination(Destination),
+    NewDestinationName(String),
+    NewDestinationKind(TargetKind),
+    NewDestinationPath(String),
+    BrowseDestination,
+    DestinationFolderPicked(Option<PathBuf>),
+    AddDestination,
+    DestinationValidated(Result<Destination, DownloaderError>),
+    RemoveDestination(usize),
+    DetectDestinations,
+    SettingsSource(&'static str, bool),
+    SettingsChannel(&'static str, Channel),
+    ShowUnstable(bool),
+    SearchReleases(String),
+    SearchSettled(u64),
+    FilterReleases(ReleaseFilter),
+    SortReleases(ReleaseSort),
+    PreferenceSaved(Result<(), DownloaderError>),
+    SettingsParallelDownloads(u32),
+    SettingsRetention(u32),
+    SettingsProxy(String),
+    SettingsProxyUser(String),
+    SettingsProxyPassword(String),
+    SettingsNoProxy(String),
+    SettingsCacheLimit(u32),
+    SettingsBandwidthLimit(Rate),
+    SettingsUpdatePolicy(UpdatePolicy),
+    SettingsCheckInterval(u32),
+    SetRateLimit(Rate),
+    SetJobRateLimit(String, Rate),
+    ShowCache,
+    RemoveCached(String),
+    ClearCache,
+    SaveSettings,
+    SettingsSaved(Result<(), DownloaderError>),
+    Notification(notify::Event),
+    ShowLog,
+    LogLevel(Level),
+    LogJob(JobFilter),
+    RefreshLog,
+    Action(Action),
+    PaletteQuery(String),
+    PaletteSubmit,
+    PaletteChoose(Action),
 }
 
 impl Application for Downloader {
     type Message = Message;
     type Executor = executor::Default;
     type Theme = Theme;
-    type Flags = ();
+    type Flags = Config;
 
     fn theme(&self) -> Theme {
-        Theme::Dark
+        match self.config.theme {
+            ThemeChoice::Dark => Theme::Dark,
+            ThemeChoice::Light => Theme::Light,
+        }
     }
 
-    fn new(_flags: Self::Flags) -> (Self, Command<Message>) {
+    fn new(flags: Self::Flags) -> (Self, Command<Message>) {
+        let sources = flags.sources.clone();
+        let destination = flags.default_destination().cloned();
         (
             Self {
+                config: flags.clone(),
+                screen: Screen::Releases,
+                draft: flags,
+                new_destination_name: String::new(),
+                new_destination_kind: TargetKind::Steam,
+                new_destination_path: String::new(),
+                draft_proxy: String::new(),
+                draft_no_proxy: String::new(),
                 content: Vec::new(),
-                status: String::from_str("Loading available releases").unwrap(),
+                destination,
+                status: tr!("status-loading"),
                 proton_list: HashMap::new(),
+                installed: Vec::new(),
+                in_use: HashSet::new(),
+                installing: HashMap::new(),
+                selected: None,
+                queued: VecDeque::new(),
+                pending_steam_action: None,
+                steam_prompt: false,
+                pending_removal: None,
+                cache_entries: Vec::new(),
+                rate_limit: ratelimit::global(),
+                verify_reports: HashMap::new(),
+                notifier: None,
+                notified: HashSet::new(),
+                log_level: Level::INFO,
+                log_job: JobFilter::All,
+                log_records: Vec::new(),
+                log_jobs: Vec::new(),
+                palette: None,
+                search_edits: 0,
             },
             // Command::none(),
-            Command::perform(get_releases(), Message::ReleasesLoaded),
+            Command::perform(get_releases(sources), Message::ReleasesLoaded),
         )
     }
 
@@ -75,78 +361,1518 @@ impl Application for Downloader {
         match message {
             Message::ReleasesLoaded(Ok(content)) => {
                 self.content = content;
-                self.status =
-                    String::from_str("Checking installed packages in file system").unwrap();
-                Command::perform(get_installed_wrapper(), Message::FilesystemLoaded)
+                self.status = tr!("status-scanning");
+                self.rescan()
                 //let _installed = get_installed().unwrap();
             }
             Message::ReleasesLoaded(Err(_e)) => {
-                self.status = String::from_str("Failed to load data").unwrap();
+                self.status = tr!("status-load-failed");
                 Command::none()
             }
             Message::FilesystemLoaded(Ok(content)) => {
-                self.status = String::from_str("Done").unwrap();
-                for item in &self.content {
-                    if let Ok(proton) = Proton::new(item.clone(), content.contains(&item.tag_name))
-                    {
-                        self.proton_list.insert(item.tag_name.clone(), proton);
+                self.status = tr!("status-done");
+                self.verify_reports.clear();
+                let cached: HashSet<String> = cache::entries()
+                    .into_iter()
+                    .map(|entry| entry.name)
+                    .collect();
+                for (source, item) in &self.content {
+                    let key = proton::release_key(source, item);
+                    if self.is_busy(&key) {
+                        continue;
+                    }
+                    let release =
+                        build_release(source, item, self.destination.as_ref(), &content, &cached);
+                    if let Ok(proton) = release {
+                        self.proton_list.insert(key, proton);
                     }
                 }
+                self.installed = content;
+                self.in_use = match &self.destination {
+                    Some(destination) if destination.kind == TargetKind::Steam => {
+                        steam::root_for(&destination.path)
+                            .map(|root| steam::tools_in_use(&root))
+                            .unwrap_or_default()
+                    }
+                    _ => HashSet::new(),
+                };
+                self.notify_updates();
                 Command::none()
             }
             Message::FilesystemLoaded(Err(_e)) => {
-                self.status = String::from_str("Failed to check files in local folder").unwrap();
+                self.status = tr!("status-scan-failed");
                 Command::none()
             }
             Message::Refresh => {
-                self.proton_list.clear();
-                Command::none()
+                let busy: Vec<String> = self
+                    .proton_list
+                    .keys()
+                    .filter(|name| self.is_busy(name))
+                    .cloned()
+                    .collect();
+                self.proton_list.retain(|name, _| busy.contains(name));
+                self.status = tr!("status-loading");
+                Command::perform(
+                    get_releases(self.config.sources.clone()),
+                    Message::ReleasesLoaded,
+                )
             }
             Message::Install(name) => {
-                /*
-                println!("Installing {}", name.tag_name);
-                //println!("{:?}", name.assets);
-                if let Some(url) = name.tarball_url {
-                    println!("{:?}", url);
-                    println!("{:?}", url.host());
-                }*/
-                //Command::none()
-                //Command::perform(item.install(), Message::Installed)
-                //Command::perform(download_proton(name), Message::Installed)
+                let destination = match &self.destination {
+                    Some(destination) => destination.clone(),
+                    None => return Command::none(),
+                };
+                if let Some(proton) = self.proton_list.get_mut(&name) {
+                    if let Err(e) = proton.set_status(proton_status::Downloading) {
+                        self.status = e.to_string();
+                        return Command::none();
+                    }
+                    if self.installing.len() < self.config.parallel_downloads.max(1) as usize {
+                        self.installing.insert(
+                            name,
+                            Job {
+                                destination,
+                                progress: Progress::Started,
+                                file: None,
+                                rate: Rate(0),
+                            },
+                        );
+                    } else {
+                        self.queued.push_back((name, destination));
+                    }
+                }
                 Command::none()
             }
+            Message::SelectRelease(name) => {
+                self.selected = Some(name);
+                Command::none()
+            }
+            Message::CancelInstall(name) => {
+                // Extraction can't be interrupted without leaving half a tool behind.
+                let downloading = matches!(
+                    self.proton_list
+                        .get(&name)
+                        .map(|proton| proton.get_status()),
+                    Some(proton_status::Downloading)
+                );
+                if !downloading {
+                    return Command::none();
+                }
+                ratelimit::set_job(&name, Rate(0));
+                self.installing.remove(&name);
+                self.queued.retain(|(queued, _)| *queued != name);
+                self.start_queued();
+                self.set_release_status(&name, self.fallback_status(&name));
+                self.status = tr!("status-cancelled", name = self.display_name(&name));
+                Command::none()
+            }
+            Message::RemoveRelease(name) => {
+                let (destination, proton) = match (&self.destination, self.proton_list.get(&name)) {
+                    (Some(destination), Some(proton)) => (destination.clone(), proton),
+                    _ => return Command::none(),
+                };
+                let tools: Vec<&InstalledTool> = self
+                    .installed
+                    .iter()
+                    .filter(|tool| tool.matches(&proton.get_name()))
+                    .collect();
+                if let Some(tool) = tools.iter().find(|tool| self.in_use.contains(tool.name())) {
+                    self.status = tr!("status-tool-in-use", name = tool.name());
+                    return Command::none();
+                }
+                if !tools.is_empty() {
+                    self.pending_removal = Some(Removal {
+                        destination,
+                        tools: tools.into_iter().cloned().collect(),
+                    });
+                }
+                Command::none()
+            }
+            Message::VerifyRelease(name) => {
+                let tool = self.proton_list.get(&name).and_then(|proton| {
+                    self.installed
+                        .iter()
+                        .find(|tool| tool.matches(&proton.get_name()))
+                });
+                match tool {
+                    Some(tool) => self.update(Message::VerifyTool(tool.path.clone())),
+                    None => Command::none(),
+                }
+            }
+            Message::InstallFromFile => Command::perform(pick_tarball(), Message::FilePicked),
+            Message::FilePicked(Some(path)) | Message::FileDropped(path) => {
+                let destination = match &self.destination {
+                    Some(destination) => destination.clone(),
+                    None => {
+                        self.status = tr!("status-no-destination");
+                        return Command::none();
+                    }
+                };
+                let key = format!("file:{}", path.display());
+                self.installing.entry(key).or_insert_with(|| Job {
+                    destination,
+                    progress: Progress::Started,
+                    file: Some(path),
+                    rate: Rate(0),
+                });
+                Command::none()
+            }
+            Message::FilePicked(None) => Command::none(),
+            Message::InstallProgress((name, progress)) => match progress {
+                Progress::Finished => {
+                    ratelimit::set_job(&name, Rate(0));
+                    let job = self.installing.remove(&name);
+                    self.start_queued();
+                    self.set_release_status(&name, proton_status::Installed);
+                    self.notify(Notification::new(
+                        tr!("notify-installed", name = self.display_name(&name)),
+                        tr!("notify-installed-body"),
+                    ));
+                    self.status = tr!("status-installed", name = self.display_name(&name));
+                    let source = self
+                        .proton_list
+                        .get(&name)
+                        .map(|proton| proton.get_source());
+                    match job {
+                        Some(job) => {
+                            let destination = job.destination.clone();
+                            Command::perform(
+                                prune_candidates(job.destination, source, self.config.retention),
+                                move |result| Message::PruneCandidates(destination.clone(), result),
+                            )
+                        }
+                        None => Command::none(),
+                    }
+                }
+                Progress::Errored(e) => {
+                    ratelimit::set_job(&name, Rate(0));
+                    self.installing.remove(&name);
+                    self.start_queued();
+                    self.set_release_status(&name, self.fallback_status(&name));
+                    self.status = tr!(
+                        "status-install-failed",
+                        name = self.display_name(&name),
+                        error = e.to_string()
+                    );
+                    self.notify(
+                        Notification::new(
+                            tr!("notify-install-failed", name = self.display_name(&name)),
+                            e.to_string(),
+                        )
+                        .critical(),
+                    );
+                    Command::none()
+                }
+                Progress::Signed(signer) => {
+                    if let Some(proton) = self.proton_list.get_mut(&name) {
+                        proton.set_signed_by(signer);
+                    }
+                    self.set_release_status(&name, proton_status::Installing);
+                    if let Some(job) = self.installing.get_mut(&name) {
+                        job.progress = Progress::Installing;
+                    }
+                    Command::none()
+                }
+                progress => {
+                    if let Progress::Installing = progress {
+                        self.set_release_status(&name, proton_status::Installing);
+                    }
+                    if let Some(job) = self.installing.get_mut(&name) {
+                        job.progress = progress;
+                    }
+                    Command::none()
+                }
+            },
             Message::Installed(success) => {
-                println!("Installed");
                 match success {
-                    Ok(_) => println!("Success"),
-                    Err(e) => println!("error {:?}", e),
+                    Ok(_) => tracing::info!("installed"),
+                    Err(e) => tracing::error!("install failed: {}", e),
+                }
+                Command::none()
+            }
+            Message::Pruned(destination, result) => {
+                if self.destination.as_ref() == Some(&destination) {
+                    self.update(Message::FilesystemLoaded(result))
+                } else {
+                    if let Err(e) = result {
+                        self.status = tr!(
+                            "status-cleanup-failed",
+                            destination = destination.to_string(),
+                            error = e.to_string()
+                        );
+                    }
+                    Command::none()
+                }
+            }
+            Message::PruneCandidates(destination, Ok((tools, candidates))) => {
+                if !candidates.is_empty() {
+                    self.pending_removal = Some(Removal {
+                        destination: destination.clone(),
+                        tools: candidates,
+                    });
+                }
+                self.update(Message::Pruned(destination, Ok(tools)))
+            }
+            Message::PruneCandidates(destination, Err(e)) => {
+                self.update(Message::Pruned(destination, Err(e)))
+            }
+            Message::ConfirmRemoval => match self.pending_removal.take() {
+                Some(removal) => {
+                    let paths = removal.tools.iter().map(|tool| tool.path.clone()).collect();
+                    let names = removal.names();
+                    let destination = removal.destination.clone();
+                    Command::perform(uninstall(removal.destination, paths), move |result| {
+                        Message::Removed(names.clone(), destination.clone(), result)
+                    })
+                }
+                None => Command::none(),
+            },
+            Message::Removed(names, destination, result) => {
+                let status = match &result {
+                    Ok(_) => tr!("status-removed", name = names.as_str()),
+                    Err(e) => tr!(
+                        "status-remove-failed",
+                        name = names.as_str(),
+                        error = e.to_string()
+                    ),
+                };
+                // A failure can leave a tool half deleted, so rescan either way.
+                let command = match result {
+                    Ok(tools) => self.update(Message::Pruned(destination, Ok(tools))),
+                    Err(_) if self.destination.as_ref() == Some(&destination) => self.rescan(),
+                    Err(_) => Command::none(),
+                };
+                self.status = status;
+                command
+            }
+            Message::CancelRemoval => {
+                self.pending_removal = None;
+                Command::none()
+            }
+            Message::SelectDestination(destination) => {
+                self.destination = Some(destination);
+                self.status = tr!("status-scanning");
+                self.rescan()
+            }
+            Message::VerifyTool(path) => {
+                self.verify_reports.remove(&path);
+                let verified = path.clone();
+                Command::perform(verify_tool(path), move |report| {
+                    Message::ToolVerified(verified.clone(), report)
+                })
+            }
+            Message::ToolVerified(path, report) => {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                match &report {
+                    Ok(report) => {
+                        for file in &report.missing {
+                            tracing::warn!(tool = %name, "missing: {}", file.display());
+                        }
+                        for file in &report.modified {
+                            tracing::warn!(tool = %name, "modified: {}", file.display());
+                        }
+                        for file in &report.extra {
+                            tracing::warn!(tool = %name, "extra: {}", file.display());
+                        }
+                        self.status =
+                            tr!("status-verified", name = name, report = report.to_string());
+                    }
+                    Err(e) => {
+                        self.status =
+                            tr!("status-verify-failed", name = name, error = e.to_string())
+                    }
+                }
+                self.verify_reports.insert(path, report);
+                Command::none()
+            }
+            Message::RepairTool(path) => {
+                let download = self
+                    .installed
+                    .iter()
+                    .find(|tool| tool.path == path)
+                    .and_then(|tool| {
+                        self.proton_list
+                            .values()
+                            .find(|proton| tool.matches(&proton.get_name()))
+                    })
+                    .map(|proton| {
+                        let keys = self.config.keys_for(proton.get_source().id);
+                        install::RepairSource::new(proton, keys)
+                    });
+                self.verify_reports.remove(&path);
+                self.status = tr!("status-repairing", path = path.display().to_string());
+                let repaired = path.clone();
+                Command::perform(
+                    install::repair(path, download, self.config.cache_limit()),
+                    move |report| Message::ToolRepaired(repaired.clone(), report),
+                )
+            }
+            Message::ToolRepaired(path, report) => {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                self.status = match &report {
+                    Ok(report) => tr!("status-repaired", name = name, report = report.to_string()),
+                    Err(e) => tr!("status-repair-failed", name = name, error = e.to_string()),
+                };
+                self.verify_reports.insert(path, report);
+                Command::none()
+            }
+            Message::SetDefaultTool(name) => {
+                let root = self
+                    .destination
+                    .as_ref()
+                    .and_then(|destination| steam::root_for(&destination.path));
+                match root {
+                    Some(root) => self.run_steam_action(SteamAction::SetDefaultTool(root, name)),
+                    None => {
+                        self.status = tr!("status-steam-update-failed");
+                        Command::none()
+                    }
+                }
+            }
+            Message::SteamConfigUpdated(Ok(())) => {
+                self.pending_steam_action = None;
+                self.steam_prompt = false;
+                self.status = tr!("status-steam-updated");
+                Command::none()
+            }
+            Message::SteamConfigUpdated(Err(DownloaderError::SteamRunning)) => {
+                self.steam_prompt = true;
+                self.status = tr!("status-steam-running");
+                Command::none()
+            }
+            Message::SteamConfigUpdated(Err(_e)) => {
+                self.pending_steam_action = None;
+                self.status = tr!("status-steam-update-failed");
+                Command::none()
+            }
+            Message::ShutdownSteam => {
+                self.steam_prompt = false;
+                self.status = tr!("status-steam-waiting");
+                let root = match &self.pending_steam_action {
+                    Some(SteamAction::SetDefaultTool(root, _)) => root.clone(),
+                    None => return Command::none(),
+                };
+                Command::perform(steam::shutdown_and_wait(root), Message::SteamExited)
+            }
+            Message::CancelSteamAction => {
+                self.pending_steam_action = None;
+                self.steam_prompt = false;
+                self.status = tr!("status-done");
+                Command::none()
+            }
+            Message::SteamExited(Ok(())) => match self.pending_steam_action.clone() {
+                Some(action) => self.run_steam_action(action),
+                None => Command::none(),
+            },
+            Message::SteamExited(Err(_e)) => {
+                self.pending_steam_action = None;
+                self.status = tr!("status-steam-shutdown-failed");
+                Command::none()
+            }
+            Message::ShowSettings => {
+                self.draft = self.config.clone();
+                self.draft_proxy = self.config.proxy.clone().unwrap_or_default();
+                self.draft_no_proxy = self.config.no_proxy.join(", ");
+                self.screen = Screen::Settings;
+                Command::none()
+            }
+            Message::ShowReleases => {
+                self.screen = Screen::Releases;
+                Command::none()
+            }
+            Message::SettingsTheme(theme) => {
+                self.draft.theme = theme;
+                Command::none()
+            }
+            Message::SettingsLanguage(language) => {
+                self.draft.language = language.setting();
+                Command::none()
+            }
+            Message::SettingsDefaultDestination(destination) => {
+                self.draft.default_destination = Some(destination.name);
+                Command::none()
+            }
+            Message::NewDestinationName(name) => {
+                self.new_destination_name = name;
+                Command::none()
+            }
+            Message::NewDestinationKind(kind) => {
+                self.new_destination_kind = kind;
+                if self.new_destination_path.is_empty() {
+                    if let Some(path) = kind.default_path() {
+                        self.new_destination_path = path.display().to_string();
+                    }
+                }
+                Command::none()
+            }
+            Message::NewDestinationPath(path) => {
+                self.new_destination_path = path;
+                Command::none()
+            }
+            Message::BrowseDestination => Command::perform(
+                pick_folder(self.new_destination_kind.default_path()),
+                Message::DestinationFolderPicked,
+            ),
+            Message::DestinationFolderPicked(Some(path)) => {
+                self.new_destination_path = path.display().to_string();
+                Command::none()
+            }
+            Message::DestinationFolderPicked(None) => Command::none(),
+            Message::AddDestination => {
+                let name = self.new_destination_name.trim().to_string();
+                if self.draft.destinations.iter().any(|d| d.name == name) {
+                    self.status = tr!("status-destination-exists", name = name.as_str());
+                    return Command::none();
+                }
+                Command::perform(
+                    target::validate(Destination {
+                        name,
+                        kind: self.new_destination_kind,
+                        path: PathBuf::from(self.new_destination_path.trim()),
+                    }),
+                    Message::DestinationValidated,
+                )
+            }
+            Message::DestinationValidated(Ok(destination)) => {
+                self.draft.destinations.push(destination);
+                self.new_destination_name.clear();
+                self.new_destination_path.clear();
+                Command::none()
+            }
+            Message::DestinationValidated(Err(e)) => {
+                self.status = tr!("status-destination-invalid", error = e.to_string());
+                Command::none()
+            }
+            Message::RemoveDestination(index) => {
+                if index < self.draft.destinations.len() {
+                    self.draft.destinations.remove(index);
+                }
+                Command::none()
+            }
+            Message::DetectDestinations => {
+                for detected in target::detect() {
+                    if !self
+                        .draft
+                        .destinations
+                        .iter()
+                        .any(|d| d.path == detected.path || d.name == detected.name)
+                    {
+                        self.draft.destinations.push(detected);
+                    }
+                }
+                Command::none()
+            }
+            Message::SettingsSource(id, enabled) => {
+                self.draft.sources.retain(|source| source != id);
+                if enabled {
+                    self.draft.sources.push(id.to_string());
+                }
+                Command::none()
+            }
+            Message::SettingsChannel(id, channel) => {
+                if channel == Channel::Stable {
+                    self.draft.channels.remove(id);
+                } else {
+                    self.draft.channels.insert(id.to_string(), channel);
+                }
+                Command::none()
+            }
+            Message::ShowUnstable(show) => {
+                self.config.show_unstable = show;
+                self.draft.show_unstable = show;
+                self.save_preferences()
+            }
+            Message::SearchReleases(search) => {
+                self.config.release_search = search.clone();
+                self.draft.release_search = search;
+                self.search_edits += 1;
+                let edit = self.search_edits;
+                Command::perform(tokio::time::sleep(SEARCH_SAVE_DELAY), move |_| {
+                    Message::SearchSettled(edit)
+                })
+            }
+            Message::SearchSettled(edit) if edit == self.search_edits => self.save_preferences(),
+            Message::SearchSettled(_) => Command::none(),
+            Message::FilterReleases(filter) => {
+                self.config.release_filter = filter;
+                self.draft.release_filter = filter;
+                self.save_preferences()
+            }
+            Message::SortReleases(sort) => {
+                self.config.release_sort = sort;
+                self.draft.release_sort = sort;
+                self.save_preferences()
+            }
+            Message::PreferenceSaved(Ok(())) => Command::none(),
+            Message::PreferenceSaved(Err(e)) => {
+                self.status = tr!("status-settings-save-failed", error = e.to_string());
+                Command::none()
+            }
+            Message::SettingsParallelDownloads(count) => {
+                self.draft.parallel_downloads = count;
+                Command::none()
+            }
+            Message::SettingsRetention(count) => {
+                self.draft.retention = count;
+                Command::none()
+            }
+            Message::SettingsProxy(proxy) => {
+                self.draft_proxy = proxy;
+                Command::none()
+            }
+            Message::SettingsProxyUser(user) => {
+                self.draft.proxy_user = Some(user).filter(|user| !user.is_empty());
+                Command::none()
+            }
+            Message::SettingsProxyPassword(password) => {
+                self.draft.proxy_password = Some(password).filter(|password| !password.is_empty());
+                Command::none()
+            }
+            Message::SettingsNoProxy(hosts) => {
+                self.draft_no_proxy = hosts;
+                Command::none()
+            }
+            Message::SettingsCacheLimit(gib) => {
+                self.draft.cache_limit_mb = gib as u64 * 1024;
+                Command::none()
+            }
+            Message::SettingsBandwidthLimit(rate) => {
+                self.draft.bandwidth_limit = rate.0;
+                Command::none()
+            }
+            Message::SettingsUpdatePolicy(policy) => {
+                self.draft.update_policy = policy;
+                Command::none()
+            }
+            Message::SettingsCheckInterval(hours) => {
+                self.draft.check_interval_hours = hours;
+                Command::none()
+            }
+            Message::SetRateLimit(rate) => {
+                self.rate_limit = rate;
+                ratelimit::set_global(rate);
+                Command::none()
+            }
+            Message::SetJobRateLimit(name, rate) => {
+                if let Some(job) = self.installing.get_mut(&name) {
+                    job.rate = rate;
+                    ratelimit::set_job(&name, rate);
+                }
+                Command::none()
+            }
+            Message::ShowCache => {
+                self.cache_entries = cache::entries();
+                self.screen = Screen::Cache;
+                Command::none()
+            }
+            Message::RemoveCached(hash) => {
+                if let Err(e) = cache::remove(&hash) {
+                    self.status = tr!("status-cache-remove-failed", error = e.to_string());
+                }
+                self.cache_entries = cache::entries();
+                Command::none()
+            }
+            Message::ClearCache => {
+                if let Err(e) = cache::clear() {
+                    self.status = tr!("status-cache-clear-failed", error = e.to_string());
+                }
+                self.cache_entries = cache::entries();
+                Command::none()
+            }
+            Message::SaveSettings => {
+                let proxy = self.draft_proxy.trim();
+                self.draft.proxy = if proxy.is_empty() {
+                    None
+                } else {
+                    Some(proxy.to_string())
+                };
+                self.draft.no_proxy = self
+                    .draft_no_proxy
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|host| !host.is_empty())
+                    .map(String::from)
+                    .collect();
+                if let Err(e) = http::build_client(&self.draft) {
+                    self.status = e.to_string();
+                    return Command::none();
+                }
+                Command::perform(settings::save(self.draft.clone()), Message::SettingsSaved)
+            }
+            Message::SettingsSaved(Ok(())) => {
+                let reload = self.draft.sources != self.config.sources
+                    || self.draft.destinations != self.config.destinations;
+                if self.draft.bandwidth_limit != self.config.bandwidth_limit {
+                    self.rate_limit = Rate(self.draft.bandwidth_limit);
+                    ratelimit::set_global(self.rate_limit);
+                }
+                self.config = self.draft.clone();
+                i18n::set_language(self.config.language.as_deref());
+                if let Err(e) = http::configure(&self.config) {
+                    tracing::warn!("failed to apply proxy settings: {}", e);
+                }
+                if let Err(e) = cache::evict(self.config.cache_limit()) {
+                    tracing::warn!("failed to shrink the tarball cache: {}", e);
+                }
+                let selected = self
+                    .destination
+                    .as_ref()
+                    .and_then(|destination| self.config.destination(&destination.name))
+                    .filter(|destination| Some(*destination) == self.destination.as_ref())
+                    .or(self.config.default_destination())
+                    .cloned();
+                self.destination = selected;
+                self.screen = Screen::Releases;
+                self.status = tr!("status-settings-saved");
+                if reload {
+                    self.update(Message::Refresh)
+                } else {
+                    Command::none()
+                }
+            }
+            Message::SettingsSaved(Err(e)) => {
+                self.status = tr!("status-settings-save-failed", error = e.to_string());
+                Command::none()
+            }
+            Message::Notification(notify::Event::Ready(notifier)) => {
+                self.notifier = Some(notifier);
+                self.notify_updates();
+                Command::none()
+            }
+            Message::Notification(notify::Event::Action(action)) => {
+                match action.strip_prefix(INSTALL_ACTION) {
+                    Some(key) if self.proton_list.contains_key(key) => {
+                        self.update(Message::Install(key.to_string()))
+                    }
+                    _ => Command::none(),
+                }
+            }
+            Message::ShowLog => {
+                self.screen = Screen::Log;
+                self.update(Message::RefreshLog)
+            }
+            Message::LogLevel(level) => {
+                self.log_level = level;
+                self.update(Message::RefreshLog)
+            }
+            Message::LogJob(job) => {
+                self.log_job = job;
+                self.update(Message::RefreshLog)
+            }
+            Message::RefreshLog => {
+                self.log_records = logging::records(self.log_level, &self.log_job);
+                self.log_jobs = logging::jobs();
+                Command::none()
+            }
+            Message::Action(action) => self.run_action(action),
+            Message::PaletteQuery(query) => {
+                if let Some(palette) = &mut self.palette {
+                    palette.query = query;
+                    palette.highlighted = 0;
                 }
                 Command::none()
             }
+            Message::PaletteSubmit => {
+                let chosen = self.palette.as_ref().and_then(|palette| {
+                    palette::filter(self.palette_entries(), &palette.query)
+                        .into_iter()
+                        .nth(palette.highlighted)
+                });
+                match chosen {
+                    Some(entry) => self.update(Message::PaletteChoose(entry.action)),
+                    None => Command::none(),
+                }
+            }
+            Message::PaletteChoose(action) => {
+                self.palette = None;
+                self.run_action(action)
+            }
         }
     }
 
+    fn subscription(&self) -> Subscription<Message> {
+        let installs = self.installing.iter().filter_map(|(name, job)| {
+            let install = match &job.file {
+                Some(file) => {
+                    install::install_file(name.clone(), file.clone(), job.destination.path.clone())
+                }
+                None => {
+                    let proton = self.proton_list.get(name)?;
+                    install::install(
+                        proton,
+                        job.destination.path.clone(),
+                        self.config.cache_limit(),
+                        self.config.keys_for(proton.get_source().id),
+                    )
+                }
+            };
+            Some(install.map(Message::InstallProgress))
+        });
+
+        let events = iced::subscription::events_with(|event, status| match event {
+            Event::Window(window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code,
+                modifiers,
+            }) => palette::shortcut(key_code, modifiers, status == event::Status::Captured)
+                .map(Message::Action),
+            _ => None,
+        });
+
+        let notifications = notify::notifications().map(Message::Notification);
+
+        // Records arrive without a message, so the open log panel polls.
+        let log = match self.screen {
+            Screen::Log => iced::time::every(Duration::from_secs(1)).map(|_| Message::RefreshLog),
+            _ => Subscription::none(),
+        };
+
+        Subscription::batch(installs.chain([events, notifications, log]))
+    }
+
     fn view(&self) -> Element<'_, Message> {
-        let controls: Row<'_, Message> = row![horizontal_space(Length::Fill), button("refresh")];
+        match self.screen {
+            Screen::Settings => return self.settings_view(),
+            Screen::Cache => return self.cache_view(),
+            Screen::Log => return self.log_view(),
+            Screen::Releases => {}
+        }
 
-        let content = self.get_list();
+        let controls: Row<'_, Message> = row![
+            text(tr!("install-to")),
+            pick_list(
+                self.config.destinations.clone(),
+                self.destination.clone(),
+                Message::SelectDestination
+            ),
+            horizontal_space(Length::Fill),
+            checkbox(
+                tr!("show-prereleases"),
+                self.config.show_unstable,
+                Message::ShowUnstable
+            ),
+            text(tr!("limit")),
+            pick_list(
+                Rate::choices(self.rate_limit),
+                Some(self.rate_limit),
+                Message::SetRateLimit
+            ),
+            button(text(tr!("install-from-file"))).on_press(Message::InstallFromFile),
+            button(text(tr!("cache"))).on_press(Message::ShowCache),
+            button(text(tr!("log"))).on_press(Message::ShowLog),
+            button(text(tr!("settings"))).on_press(Message::ShowSettings),
+            button(text(tr!("refresh"))).on_press(Message::Refresh)
+        ];
+
+        let list_controls = row![
+            text_input(&tr!("search-tags"), &self.config.release_search)
+                .id(search_input())
+                .on_input(Message::SearchReleases),
+            pick_list(
+                &ReleaseFilter::ALL[..],
+                Some(self.config.release_filter),
+                Message::FilterReleases
+            ),
+            pick_list(
+                &ReleaseSort::ALL[..],
+                Some(self.config.release_sort),
+                Message::SortReleases
+            )
+        ]
+        .spacing(5);
+
+        let content = scrollable(self.get_list())
+            .id(release_list())
+            .height(Length::FillPortion(2));
+
+        let installed = self.get_installed_list();
 
         let status = text(self.status.clone());
 
-        //let proton = proton_widget(String::from_str("Test name").unwrap(), false);
+        let mut layout = column![controls, list_controls];
+
+        if let Some(palette) = &self.palette {
+            layout = layout.push(self.palette_view(palette));
+        }
+
+        for (key, proton) in self.updates() {
+            layout = layout.push(row![
+                text(tr!(
+                    "update-available",
+                    source = proton.get_source().name,
+                    name = proton.get_name()
+                )),
+                horizontal_space(Length::Fill),
+                button(text(tr!("install"))).on_press(Message::Install(key.clone()))
+            ]);
+        }
 
-        container(column![
-            //proton,
-            controls,
-            content,
-            vertical_space(Length::Fill),
-            status
-        ])
+        if let Some(removal) = &self.pending_removal {
+            layout = layout.push(row![
+                text(tr!("confirm-remove", names = removal.names())),
+                horizontal_space(Length::Fill),
+                button(text(tr!("remove"))).on_press(Message::ConfirmRemoval),
+                button(text(tr!("cancel"))).on_press(Message::CancelRemoval)
+            ]);
+        }
+
+        if self.steam_prompt {
+            layout = layout.push(row![
+                text(tr!("steam-prompt")),
+                horizontal_space(Length::Fill),
+                button(text(tr!("steam-shut-down"))).on_press(Message::ShutdownSteam),
+                button(text(tr!("cancel"))).on_press(Message::CancelSteamAction)
+            ]);
+        }
+
+        container(
+            layout
+                .push(content)
+                .push(installed)
+                .push(vertical_space(Length::Fill))
+                .push(status),
+        )
         .into()
     }
 }
 
 impl Downloader {
+    fn rescan(&self) -> Command<Message> {
+        Command::perform(
+            get_installed_wrapper(self.destination.clone()),
+            Message::FilesystemLoaded,
+        )
+    }
+
+    fn is_busy(&self, key: &str) -> bool {
+        self.installing.contains_key(key) || self.queued.iter().any(|(name, _)| name == key)
+    }
+
+    fn display_name(&self, key: &str) -> String {
+        if let Some(proton) = self.proton_list.get(key) {
+            return proton.get_name();
+        }
+        match key.strip_prefix("file:").map(std::path::Path::new) {
+            Some(path) => path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| key.to_string()),
+            None => key.to_string(),
+        }
+    }
+
+    /// Updates for the selected destination that aren't being installed.
+    fn updates(&self) -> Vec<(&String, &Proton)> {
+        let destination = match &self.destination {
+            Some(destination) => destination,
+            None => return Vec::new(),
+        };
+        check::find_updates(
+            &self.config,
+            destination,
+            &self.proton_list,
+            &self.installed,
+        )
+        .into_iter()
+        .filter(|(key, _)| !self.is_busy(key))
+        .collect()
+    }
+
+    fn notify(&self, notification: Notification) {
+        if let Some(notifier) = &self.notifier {
+            notifier.send(notification);
+        }
+    }
+
+    /// Announces updates found since the last call, with a button to start
+    /// installing them.
+    fn notify_updates(&mut self) {
+        if self.notifier.is_none() {
+            return;
+        }
+        let updates: Vec<(String, Notification)> = self
+            .updates()
+            .into_iter()
+            .filter(|(key, _)| !self.notified.contains(*key))
+            .map(|(key, proton)| {
+                let notification = Notification::new(
+                    tr!("notify-available", name = proton.get_name()),
+                    tr!("notify-available-body", source = proton.get_source().name),
+                )
+                .action(format!("{}{}", INSTALL_ACTION, key), tr!("install-now"));
+                (key.clone(), notification)
+            })
+            .collect();
+        for (key, notification) in updates {
+            self.notify(notification);
+            self.notified.insert(key);
+        }
+    }
+
+    /// Moves a release to `status`, reporting transitions the state machine
+    /// doesn't allow instead of applying them.
+    fn set_release_status(&mut self, key: &str, status: proton_status) {
+        if let Some(proton) = self.proton_list.get_mut(key) {
+            if let Err(e) = proton.set_status(status) {
+                tracing::warn!(job = %key, "{}", e);
+            }
+        }
+    }
+
+    /// Where a release ends up when its install stops early, depending on
+    /// whether the tarball made it into the cache.
+    fn fallback_status(&self, key: &str) -> proton_status {
+        let cached = self.proton_list.get(key).is_some_and(|proton| {
+            let name = proton.get_tarball_name();
+            cache::entries().iter().any(|entry| entry.name == name)
+        });
+        if cached {
+            proton_status::Downloaded
+        } else {
+            proton_status::Uninstalled
+        }
+    }
+
+    fn start_queued(&mut self) {
+        while self.installing.len() < self.config.parallel_downloads.max(1) as usize {
+            match self.queued.pop_front() {
+                Some((name, destination)) => {
+                    self.installing.insert(
+                        name,
+                        Job {
+                            destination,
+                            progress: Progress::Started,
+                            file: None,
+                            rate: Rate(0),
+                        },
+                    );
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn settings_view(&self) -> Element<'_, Message> {
+        let sources: Vec<Element<'_, Message>> = source::SOURCES
+            .iter()
+            .map(|source| {
+                row![
+                    checkbox(
+                        source.name,
+                        self.draft.source_enabled(source.id),
+                        move |enabled| Message::SettingsSource(source.id, enabled),
+                    ),
+                    horizontal_space(Length::Fill),
+                    text(tr!("updates-from")),
+                    pick_list(
+                        &Channel::ALL[..],
+                        Some(self.draft.channel(source.id)),
+                        move |channel| Message::SettingsChannel(source.id, channel)
+                    )
+                ]
+                .spacing(5)
+                .into()
+            })
+            .collect();
+
+        let destinations: Vec<Element<'_, Message>> = self
+            .draft
+            .destinations
+            .iter()
+            .enumerate()
+            .map(|(index, destination)| {
+                row![
+                    text(&destination.name),
+                    horizontal_space(Length::Fixed(10.0)),
+                    text(destination.kind),
+                    horizontal_space(Length::Fixed(10.0)),
+                    text(destination.path.display()),
+                    horizontal_space(Length::Fill),
+                    button(text(tr!("remove"))).on_press(Message::RemoveDestination(index))
+                ]
+                .into()
+            })
+            .collect();
+
+        let new_destination = row![
+            text_input(&tr!("destination-name"), &self.new_destination_name)
+                .on_input(Message::NewDestinationName),
+            pick_list(
+                &TargetKind::ALL[..],
+                Some(self.new_destination_kind),
+                Message::NewDestinationKind
+            ),
+            text_input(&tr!("destination-folder"), &self.new_destination_path)
+                .on_input(Message::NewDestinationPath),
+            button(text(tr!("browse"))).on_press(Message::BrowseDestination),
+            button(text(tr!("add"))).on_press(Message::AddDestination)
+        ]
+        .spacing(5);
+
+        let default_destination = self.draft.default_destination().cloned();
+
+        container(
+            column![
+                text(tr!("settings")).size(20),
+                row![
+                    text(tr!("install-destinations")),
+                    horizontal_space(Length::Fill),
+                    button(text(tr!("detect"))).on_press(Message::DetectDestinations)
+                ],
+                Column::with_children(destinations),
+                new_destination,
+                text(tr!("default-destination")),
+                pick_list(
+                    self.draft.destinations.clone(),
+                    default_destination,
+                    Message::SettingsDefaultDestination
+                ),
+                text(tr!("theme")),
+                pick_list(
+                    &ThemeChoice::ALL[..],
+                    Some(self.draft.theme),
+                    Message::SettingsTheme
+                ),
+                text(tr!("language")),
+                pick_list(
+                    LanguageChoice::all(),
+                    Some(LanguageChoice::from_setting(self.draft.language.as_deref())),
+                    Message::SettingsLanguage
+                ),
+                text(tr!("sources")),
+                Column::with_children(sources),
+                text(tr!(
+                    "parallel-downloads",
+                    count = self.draft.parallel_downloads
+                )),
+                slider(
+                    1..=8,
+                    self.draft.parallel_downloads,
+                    Message::SettingsParallelDownloads
+                ),
+                text(match self.draft.retention {
+                    0 => tr!("retention-all"),
+                    count => tr!("retention-newest", count = count),
+                }),
+                slider(0..=20, self.draft.retention, Message::SettingsRetention),
+                text(tr!("proxy")),
+                row![
+                    text_input("http://proxy.example:3128", &self.draft_proxy)
+                        .on_input(Message::SettingsProxy),
+                    text_input(
+                        &tr!("proxy-user"),
+                        self.draft.proxy_user.as_deref().unwrap_or_default()
+                    )
+                    .on_input(Message::SettingsProxyUser),
+                    text_input(
+                        &tr!("proxy-password"),
+                        self.draft.proxy_password.as_deref().unwrap_or_default()
+                    )
+                    .password()
+                    .on_input(Message::SettingsProxyPassword)
+                ]
+                .spacing(5),
+                text_input(&tr!("no-proxy"), &self.draft_no_proxy)
+                    .on_input(Message::SettingsNoProxy),
+                text(tr!("speed-limit")),
+                pick_list(
+                    Rate::choices(Rate(self.draft.bandwidth_limit)),
+                    Some(Rate(self.draft.bandwidth_limit)),
+                    Message::SettingsBandwidthLimit
+                ),
+                text(match self.draft.cache_limit_mb {
+                    0 => tr!("cache-disabled"),
+                    limit => tr!("cache-limit", size = format_size(limit * 1024 * 1024)),
+                }),
+                slider(
+                    0..=64,
+                    (self.draft.cache_limit_mb / 1024) as u32,
+                    Message::SettingsCacheLimit
+                ),
+                text(tr!("update-policy")),
+                pick_list(
+                    &UpdatePolicy::ALL[..],
+                    Some(self.draft.update_policy),
+                    Message::SettingsUpdatePolicy
+                ),
+                text(tr!(
+                    "check-interval",
+                    hours = self.draft.check_interval_hours
+                )),
+                slider(
+                    1..=168,
+                    self.draft.check_interval_hours,
+                    Message::SettingsCheckInterval
+                ),
+                vertical_space(Length::Fill),
+                row![
+                    text(self.status.clone()),
+                    horizontal_space(Length::Fill),
+                    button(text(tr!("cancel"))).on_press(Message::ShowReleases),
+                    button(text(tr!("save"))).on_press(Message::SaveSettings)
+                ]
+                .spacing(5),
+            ]
+            .spacing(5)
+            .padding(10),
+        )
+        .into()
+    }
+
+    fn cache_view(&self) -> Element<'_, Message> {
+        let total: u64 = self.cache_entries.iter().map(|entry| entry.size).sum();
+
+        let entries: Vec<Element<'_, Message>> = self
+            .cache_entries
+            .iter()
+            .map(|entry| {
+                row![
+                    text(&entry.name),
+                    horizontal_space(Length::Fixed(10.0)),
+                    text(format_size(entry.size)),
+                    horizontal_space(Length::Fill),
+                    button(text(tr!("remove"))).on_press(Message::RemoveCached(entry.hash.clone()))
+                ]
+                .into()
+            })
+            .collect();
+
+        container(
+            column![
+                row![
+                    text(tr!("cached-tarballs")).size(20),
+                    horizontal_space(Length::Fill),
+                    button(text(tr!("clear"))).on_press(Message::ClearCache),
+                    button(text(tr!("back"))).on_press(Message::ShowReleases)
+                ]
+                .spacing(5),
+                text(tr!(
+                    "cache-used",
+                    used = format_size(total),
+                    limit = format_size(self.config.cache_limit())
+                )),
+                Column::with_children(entries),
+                vertical_space(Length::Fill),
+                text(self.status.clone()),
+            ]
+            .spacing(5)
+            .padding(10),
+        )
+        .into()
+    }
+
+    fn palette_view(&self, palette: &Palette) -> Element<'_, Message> {
+        let matches = palette::filter(self.palette_entries(), &palette.query);
+        let mut entries: Vec<Element<'_, Message>> = matches
+            .into_iter()
+            .take(PALETTE_MATCHES)
+            .enumerate()
+            .map(|(index, entry)| {
+                let style = if index == palette.highlighted {
+                    theme::Button::Primary
+                } else {
+                    theme::Button::Text
+                };
+                button(row![
+                    text(entry.label),
+                    horizontal_space(Length::Fill),
+                    text(entry.hint).size(14)
+                ])
+                .width(Length::Fill)
+                .style(style)
+                .on_press(Message::PaletteChoose(entry.action))
+                .into()
+            })
+            .collect();
+        if entries.is_empty() {
+            entries.push(text(tr!("palette-no-matches")).into());
+        }
+
+        container(
+            column![
+                text_input(&tr!("palette-placeholder"), &palette.query)
+                    .id(palette_input())
+                    .on_input(Message::PaletteQuery)
+                    .on_submit(Message::PaletteSubmit),
+                Column::with_children(entries)
+            ]
+            .spacing(5),
+        )
+        .padding(5)
+        .style(theme::Container::Box)
+        .into()
+    }
+
+    /// Commands first, then the tags of the listed releases.
+    fn palette_entries(&self) -> Vec<palette::Entry> {
+        let commands = Action::COMMANDS.iter().cloned().map(palette::Entry::new);
+        let releases = self
+            .visible_releases()
+            .into_iter()
+            .map(|(key, proton)| palette::Entry {
+                action: Action::Go(key.clone()),
+                label: proton.get_name(),
+                hint: proton.get_source().name.to_string(),
+            });
+        commands.chain(releases).collect()
+    }
+
+    fn run_action(&mut self, action: Action) -> Command<Message> {
+        if let Some(query) = self.palette.as_ref().map(|palette| palette.query.clone()) {
+            let matches = palette::filter(self.palette_entries(), &query)
+                .len()
+                .min(PALETTE_MATCHES);
+            match action {
+                Action::Next | Action::Previous => {
+                    if let Some(palette) = &mut self.palette {
+                        palette.highlighted = match action {
+                            Action::Next => {
+                                (palette.highlighted + 1).min(matches.saturating_sub(1))
+                            }
+                            _ => palette.highlighted.saturating_sub(1),
+                        };
+                    }
+                    return Command::none();
+                }
+                Action::Install => return self.update(Message::PaletteSubmit),
+                Action::Close => {
+                    self.palette = None;
+                    return Command::none();
+                }
+                Action::OpenPalette => return text_input::focus(palette_input()),
+                _ => {}
+            }
+        }
+
+        // Keys for the release list do nothing on the other screens.
+        let on_list = matches!(
+            action,
+            Action::Next
+                | Action::Previous
+                | Action::First
+                | Action::Last
+                | Action::Install
+                | Action::Remove
+                | Action::Verify
+                | Action::Search
+                | Action::TogglePrereleases
+        );
+        if on_list && self.screen != Screen::Releases {
+            return Command::none();
+        }
+
+        let selected = self
+            .selected
+            .clone()
+            .and_then(|key| Some((self.proton_list.get(&key)?, key)));
+        match action {
+            Action::Next | Action::Previous | Action::First | Action::Last => {
+                let keys: Vec<String> = self
+                    .visible_releases()
+                    .into_iter()
+                    .map(|(key, _)| key.clone())
+                    .collect();
+                let current = self
+                    .selected
+                    .as_ref()
+                    .and_then(|selected| keys.iter().position(|key| key == selected));
+                let last = keys.len().saturating_sub(1);
+                let index = match (action, current) {
+                    (Action::First, _) | (Action::Next, None) => 0,
+                    (Action::Next, Some(index)) => (index + 1).min(last),
+                    (Action::Previous, Some(index)) => index.saturating_sub(1),
+                    // `Last`, or `Previous` without a selection.
+                    _ => last,
+                };
+                match keys.get(index) {
+                    Some(key) => self.select(key.clone()),
+                    None => Command::none(),
+                }
+            }
+            Action::Install => match selected {
+                Some((proton, key))
+                    if matches!(
+                        proton.get_status(),
+                        proton_status::Uninstalled | proton_status::Downloaded
+                    ) =>
+                {
+                    self.update(Message::Install(key))
+                }
+                _ => Command::none(),
+            },
+            Action::Remove => match selected {
+                Some((proton, key))
+                    if *proton.get_status() == proton_status::Installed
+                        && !self.is_in_use(proton) =>
+                {
+                    self.update(Message::RemoveRelease(key))
+                }
+                _ => Command::none(),
+            },
+            Action::Verify => match selected {
+                Some((proton, key)) if *proton.get_status() == proton_status::Installed => {
+                    self.update(Message::VerifyRelease(key))
+                }
+                _ => Command::none(),
+            },
+            Action::Refresh => self.update(Message::Refresh),
+            Action::Search => text_input::focus(search_input()),
+            Action::OpenPalette => {
+                self.screen = Screen::Releases;
+                self.palette = Some(Palette::default());
+                text_input::focus(palette_input())
+            }
+            Action::Close => match self.screen {
+                Screen::Releases if self.pending_removal.is_some() => {
+                    self.update(Message::CancelRemoval)
+                }
+                Screen::Releases => {
+                    self.selected = None;
+                    Command::none()
+                }
+                _ => self.update(Message::ShowReleases),
+            },
+            Action::InstallFromFile => self.update(Message::InstallFromFile),
+            Action::TogglePrereleases => {
+                self.update(Message::ShowUnstable(!self.config.show_unstable))
+            }
+            Action::ShowSettings => self.update(Message::ShowSettings),
+            Action::ShowCache => self.update(Message::ShowCache),
+            Action::ShowLog => self.update(Message::ShowLog),
+            Action::Go(key) => self.select(key),
+        }
+    }
+
+    /// Selects a release and scrolls the list so it is in view.
+    fn select(&mut self, key: String) -> Command<Message> {
+        let keys: Vec<&String> = self
+            .visible_releases()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        let offset = match keys.iter().position(|visible| **visible == key) {
+            Some(index) if keys.len() > 1 => index as f32 / (keys.len() - 1) as f32,
+            _ => 0.0,
+        };
+        self.selected = Some(key);
+        scrollable::snap_to(
+            release_list(),
+            scrollable::RelativeOffset { x: 0.0, y: offset },
+        )
+    }
+
+    fn log_view(&self) -> Element<'_, Message> {
+        let records: Vec<Element<'_, Message>> = self
+            .log_records
+            .iter()
+            .rev()
+            .map(|record| text(record.to_string()).size(14).into())
+            .collect();
+        let directory = paths::log_dir()
+            .map(|dir| tr!("log-directory", path = dir.display().to_string()))
+            .unwrap_or_default();
+
+        container(
+            column![
+                row![
+                    text(tr!("log")).size(20),
+                    horizontal_space(Length::Fill),
+                    pick_list(
+                        self.log_jobs.clone(),
+                        Some(self.log_job.clone()),
+                        Message::LogJob
+                    ),
+                    pick_list(
+                        &logging::LEVELS[..],
+                        Some(self.log_level),
+                        Message::LogLevel
+                    ),
+                    button(text(tr!("back"))).on_press(Message::ShowReleases)
+                ]
+                .spacing(5),
+                text(directory),
+                scrollable(Column::with_children(records)).height(Length::Fill),
+            ]
+            .spacing(5)
+            .padding(10),
+        )
+        .into()
+    }
+
+    fn run_steam_action(&mut self, action: SteamAction) -> Command<Message> {
+        self.pending_steam_action = Some(action.clone());
+        match action {
+            SteamAction::SetDefaultTool(root, name) => Command::perform(
+                steam::set_default_compat_tool(root, name),
+                Message::SteamConfigUpdated,
+            ),
+        }
+    }
+
+    fn is_in_use(&self, proton: &Proton) -> bool {
+        self.installed
+            .iter()
+            .any(|tool| tool.matches(&proton.get_name()) && self.in_use.contains(tool.name()))
+    }
+
+    /// Releases matching the search text and filter, in the selected order.
+    fn visible_releases(&self) -> Vec<(&String, &Proton)> {
+        let search = self.config.release_search.trim().to_lowercase();
+        let mut releases: Vec<(&String, &Proton)> = self
+            .proton_list
+            .iter()
+            .filter(|(name, proton)| {
+                let stable = proton.get_channel() == Channel::Stable;
+                let installed = matches!(proton.get_status(), proton_status::Installed);
+                let shown = match self.config.release_filter {
+                    ReleaseFilter::All => true,
+                    ReleaseFilter::Installed => installed,
+                    ReleaseFilter::NotInstalled => !installed,
+                    ReleaseFilter::InUse => self.is_in_use(proton),
+                    ReleaseFilter::Prerelease => !stable,
+                };
+                // Picking the pre-release filter shows them regardless of the toggle.
+                let unstable_hidden = !stable
+                    && !self.config.show_unstable
+                    && self.config.release_filter != ReleaseFilter::Prerelease
+                    && !installed
+                    && !self.is_busy(name);
+                shown
+                    && !unstable_hidden
+                    && (search.is_empty() || proton.get_name().to_lowercase().contains(&search))
+            })
+            .collect();
+
+        releases.sort_by(|(a_key, a), (b_key, b)| {
+            let position = |proton: &Proton| {
+                source::SOURCES
+                    .iter()
+                    .position(|source| source == proton.get_source())
+            };
+            let by_version = || b.get_version().cmp(&a.get_version());
+            let order = match self.config.release_sort {
+                ReleaseSort::Version => position(a).cmp(&position(b)).then_with(by_version),
+                ReleaseSort::Published => b.get_published_at().cmp(&a.get_published_at()),
+                ReleaseSort::Size => b.get_tarball_size().cmp(&a.get_tarball_size()),
+            };
+            order.then_with(by_version).then_with(|| a_key.cmp(b_key))
+        });
+        releases
+    }
+
+    fn save_preferences(&self) -> Command<Message> {
+        Command::perform(
+            settings::save(self.config.clone()),
+            Message::PreferenceSaved,
+        )
+    }
+
     fn get_list(&self) -> Element<'_, Message> {
         let mut retval: Vec<Element<'_, Message>> = Vec::new();
         /*
@@ -162,12 +1888,166 @@ impl Downloader {
                     );
                 }
         */
-        for item in self.proton_list {
+        for (name, proton) in self.visible_releases() {
+            let stable = proton.get_channel() == Channel::Stable;
+            let cancel =
+                || button(text(tr!("cancel"))).on_press(Message::CancelInstall(name.clone()));
+            let card = proton_widget(
+                proton.get_source().name,
+                proton.get_name(),
+                *proton.get_status(),
+            )
+            .detail(if stable {
+                String::new()
+            } else {
+                proton.get_channel().to_string()
+            })
+            .detail(
+                proton
+                    .get_published_at()
+                    .map(i18n::format_date)
+                    .unwrap_or_default(),
+            )
+            .detail(match proton.get_signed_by() {
+                Some(signer) => tr!("signed-by", key = signer),
+                None => String::new(),
+            })
+            .selected(self.selected.as_ref() == Some(name))
+            .on_press(Message::SelectRelease(name.clone()));
+
+            let card = match proton.get_status() {
+                proton_status::Installed => {
+                    let remove = if self.is_in_use(proton) {
+                        button(text(tr!("in-use")))
+                    } else {
+                        button(text(tr!("remove"))).on_press(Message::RemoveRelease(name.clone()))
+                    };
+                    card.push(
+                        button(text(tr!("verify"))).on_press(Message::VerifyRelease(name.clone())),
+                    )
+                    .push(remove)
+                }
+                proton_status::Downloading if !self.installing.contains_key(name) => {
+                    card.detail(tr!("queued")).push(cancel())
+                }
+                proton_status::Downloading => {
+                    match self.installing.get(name).map(|job| &job.progress) {
+                        Some(Progress::Advanced(transfer)) => {
+                            let rate = self.installing.get(name).map(|job| job.rate);
+                            let key = name.clone();
+                            let mut card = card.detail(transfer_summary(transfer));
+                            // Servers without a Content-Length only get the byte count.
+                            if let Some(percentage) = transfer.percentage() {
+                                card = card.progress(percentage);
+                            }
+                            card.push(pick_list(
+                                Rate::choices(rate.unwrap_or(Rate(0))),
+                                rate,
+                                move |rate| Message::SetJobRateLimit(key.clone(), rate),
+                            ))
+                            .push(cancel())
+                        }
+                        Some(Progress::CheckIntegrity) => card.detail(tr!("verifying")),
+                        _ => card.detail(tr!("starting")).push(cancel()),
+                    }
+                }
+                proton_status::Installing => {
+                    match self.installing.get(name).map(|job| &job.progress) {
+                        Some(Progress::CheckIntegrity) => card.detail(tr!("verifying")),
+                        _ => card.detail(tr!("extracting")),
+                    }
+                }
+                status @ (proton_status::Uninstalled | proton_status::Downloaded) => {
+                    let label = if *status == proton_status::Downloaded {
+                        tr!("install-downloaded")
+                    } else {
+                        tr!("install")
+                    };
+                    match &self.destination {
+                        Some(destination)
+                            if !destination.kind.accepts(proton.get_source().kind) =>
+                        {
+                            card.push(text(tr!("not-for", kind = destination.kind.to_string())))
+                        }
+                        Some(_) => {
+                            card.push(button(text(label)).on_press(Message::Install(name.clone())))
+                        }
+                        None => card.push(button(text(label))),
+                    }
+                }
+            };
+            retval.push(card.into());
+        }
+        for (name, job) in &self.installing {
+            if job.file.is_none() {
+                continue;
+            }
+            let card = proton_widget(
+                tr!("local-file"),
+                self.display_name(name),
+                proton_status::Installing,
+            );
+            let card = match &job.progress {
+                Progress::CheckIntegrity => card.detail(tr!("verifying")),
+                Progress::Installing => card.detail(tr!("extracting")),
+                _ => card.detail(tr!("starting")),
+            };
+            retval.push(card.into());
+        }
+        container(Column::with_children(retval)).into()
+    }
+
+    fn get_installed_list(&self) -> Element<'_, Message> {
+        let mut retval: Vec<Element<'_, Message>> = vec![text(tr!("installed")).size(20).into()];
+
+        for tool in &self.installed {
+            let version = match &tool.version {
+                Some(version) => version.clone(),
+                None => tr!("unknown-version"),
+            };
+
+            let action: Element<'_, Message> = if !tool.is_valid() {
+                text(tr!("incomplete-installation")).into()
+            } else if matches!(&self.destination, Some(d) if d.kind == TargetKind::Steam) {
+                button(text(tr!("set-default")))
+                    .on_press(Message::SetDefaultTool(tool.name().to_string()))
+                    .into()
+            } else {
+                horizontal_space(Length::Shrink).into()
+            };
+
+            let verified = match self.verify_reports.get(&tool.path) {
+                Some(Ok(report)) => report.to_string(),
+                Some(Err(_)) => tr!("not-verifiable"),
+                None => String::new(),
+            };
+            let check: Element<'_, Message> = match self.verify_reports.get(&tool.path) {
+                Some(Ok(report)) if !report.missing.is_empty() || !report.modified.is_empty() => {
+                    button(text(tr!("repair")))
+                        .on_press(Message::RepairTool(tool.path.clone()))
+                        .into()
+                }
+                _ => button(text(tr!("verify")))
+                    .on_press(Message::VerifyTool(tool.path.clone()))
+                    .into(),
+            };
+
             retval.push(
                 container(row![
-                    text(item.0),
+                    text(tool.display_name()),
+                    horizontal_space(Length::Fixed(10.0)),
+                    text(version),
+                    horizontal_space(Length::Fixed(10.0)),
+                    text(
+                        tool.size
+                            .map(format_size)
+                            .unwrap_or_else(|| tr!("size-unknown"))
+                    ),
+                    horizontal_space(Length::Fixed(10.0)),
+                    text(verified),
                     horizontal_space(Length::Fill),
-                    button("Install").on_press(Message::Install(item.0))
+                    check,
+                    action
                 ])
                 .padding(1)
                 .into(),
@@ -187,13 +2067,7 @@ fn get_proton_urls(release: &Release) -> Result<(String, String), DownloaderErro
 
     for item in &release.assets {
         let browser_url = &item.browser_download_url;
-
-        let url: String = match browser_url.host_str() {
-            Some(host) => String::from(host),
-            None => String::new(),
-        };
-
-        let url = format!("http://{}{}", url, browser_url.path());
+        let url = browser_url.to_string();
 
         if checksum_re.is_match(browser_url.path()) {
             checksum_url = url;
@@ -219,10 +2093,10 @@ async fn download_proton(release: Release) -> Result<(String), DownloaderError>
 
     let (tarball_url, checksum_url) = get_proton_urls(&release)?;
 
-    println!("tarball url: {}", tarball_url);
-    println!("checksum url: {}", checksum_url);
+    tracing::debug!("tarball url: {}", tarball_url);
+    tracing::debug!("checksum url: {}", checksum_url);
 
-    let response = reqwest::get(&checksum_url)
+    let response = http::get(&checksum_url)
         .await
         .or(Err(DownloaderError::DownloadError))?;
 
@@ -233,7 +2107,7 @@ async fn download_proton(release: Release) -> Result<(String), DownloaderError>
 
     let checksum = content;
 
-    let response = reqwest::get(&tarball_url)
+    let response = http::get(&tarball_url)
         .await
         .or(Err(DownloaderError::DownloadError))?;
 
@@ -245,7 +2119,7 @@ async fn download_proton(release: Release) -> Result<(String), DownloaderError>
     let tarball = content;
 
     let checksum: Vec<&str> = checksum.split(' ').collect();
-    println!("checksum: {}", checksum[0]);
+    tracing::debug!("checksum: {}", checksum[0]);
 
     let mut hasher = Sha512::new();
 
@@ -259,74 +2133,121 @@ async fn download_proton(release: Release) -> Result<(String), DownloaderError>
     }
 
     if caluclated_checksum.eq(&checksum[0]) {
-        println!("Checksum matches!!!");
+        tracing::info!("checksum matches");
     }
 
-    println!("calculated checksum: {}", caluclated_checksum);
+    tracing::debug!("calculated checksum: {}", caluclated_checksum);
 
     Ok(release.tag_name.clone())
 }
 
-async fn get_installed_wrapper() -> Result<Vec<String>, DownloaderError> {
-    match get_installed().await {
+async fn get_installed_wrapper(
+    destination: Option<Destination>,
+) -> Result<Vec<InstalledTool>, DownloaderError> {
+    match get_installed(destination).await {
         Ok(val) => Ok(val),
         Err(e) => Err(DownloaderError::FilesystemError),
     }
 }
 
-async fn get_installed() -> Result<Vec<String>, io::Error> {
-    let mut home_path = match home::home_dir() {
-        Some(path) => path,
+async fn get_installed(destination: Option<Destination>) -> Result<Vec<InstalledTool>, io::Error> {
+    let destination = match destination {
+        Some(destination) => destination,
         None => {
             return Err(io::Error::new(
                 io::ErrorKind::NotFound,
-                "Did not find home folder",
+                "No install destination selected",
             ))
         }
     };
 
-    if home_path.is_dir() {
-        home_path.push(".steam/steam/compatibilitytools.d");
-        let mut retval: Vec<String> = Vec::new();
-        /*
-                for entry in
-                    tokio_stream::wrappers::ReadDirStream::new(tokio::fs::read_dir(home_path).await?)
-                {
-        */
-        let mut stream =
-            tokio_stream::wrappers::ReadDirStream::new(tokio::fs::read_dir(home_path).await?);
-        while let Some(entry) = stream.next().await {
-            let entry = entry?.path();
-            if entry.is_dir() {
-                if let Some(name) = entry.file_name() {
-                    if let Some(name_str) = name.to_str() {
-                        match String::from_str(name_str) {
-                            Ok(m) => {
-                                println!("found folder {}", m);
-                                retval.push(m);
-                            }
-                            Err(_e) => {
-                                return Err(io::Error::new(
-                                    io::ErrorKind::Other,
-                                    "Failed to convert to string",
-                                ))
-                            }
-                        }
-                    }
-                }
-            }
+    tokio::task::spawn_blocking(move || destination.kind.scan(&destination.path))
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Rescans the install directory after an install and picks the builds of
+/// `source` beyond the configured retention count, returning the scan and
+/// the builds to offer for removal.
+async fn prune_candidates(
+    destination: Destination,
+    source: Option<&'static Source>,
+    retention: u32,
+) -> Result<(Vec<InstalledTool>, Vec<InstalledTool>), DownloaderError> {
+    let tools = get_installed_wrapper(Some(destination)).await?;
+
+    let candidates = match source {
+        Some(source) if retention > 0 => {
+            let in_use = steam::tools_in_use_by_any();
+            installed::prune_candidates(&tools, source, retention as usize, &in_use)
         }
-        Ok(retval)
-    } else {
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            "Did not find home folder",
-        ))
+        _ => Vec::new(),
+    };
+    Ok((tools, candidates))
+}
+
+fn transfer_summary(transfer: &install::Transfer) -> String {
+    let done = match transfer.total {
+        Some(total) => tr!(
+            "transfer-of",
+            done = format_size(transfer.downloaded),
+            total = format_size(total)
+        ),
+        None => format_size(transfer.downloaded),
+    };
+    let rate = tr!("rate-per-second", size = format_size(transfer.rate as u64));
+    match transfer.eta() {
+        Some(eta) => tr!(
+            "transfer-eta",
+            done = done,
+            rate = rate,
+            left = install::format_duration(eta)
+        ),
+        None => tr!("transfer", done = done, rate = rate),
     }
 }
 
+/// Deletes the folders of an installed release and rescans `destination`.
+async fn uninstall(
+    destination: Destination,
+    paths: Vec<PathBuf>,
+) -> Result<Vec<InstalledTool>, DownloaderError> {
+    for path in paths {
+        tokio::fs::remove_dir_all(&path)
+            .await
+            .or(Err(DownloaderError::FilesystemError))?;
+    }
+    get_installed_wrapper(Some(destination)).await
+}
+
+async fn verify_tool(path: PathBuf) -> Result<Report, DownloaderError> {
+    tokio::task::spawn_blocking(move || manifest::verify(&path))
+        .await
+        .or(Err(DownloaderError::FilesystemError))?
+}
+
+async fn pick_tarball() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title(tr!("install-from-file"))
+        .add_filter(tr!("tarball"), &["gz", "xz"])
+        .pick_file()
+        .await
+        .map(|file| file.path().to_path_buf())
+}
+
+async fn pick_folder(start: Option<PathBuf>) -> Option<PathBuf> {
+    let mut dialog = rfd::AsyncFileDialog::new().set_title(tr!("choose-destination"));
+    if let Some(start) = start.filter(|path| path.is_dir()) {
+        dialog = dialog.set_directory(start);
+    }
+    dialog
+        .pick_folder()
+        .await
+        .map(|folder| folder.path().to_path_buf())
+}
+
 async fn download_release(name: String) {
-    let octocrab = match Octocrab::builder().build() {
+    let octocrab = match http::github() {
         Ok(obj) => obj,
         Err(_e) => return,
     };
@@ -337,37 +2258,106 @@ async fn download_release(name: String) {
     .get_asset(asset_id);*/
 }
 
-async fn get_releases() -> Result<Vec<Release>, DownloaderError> {
-    let octocrab = match Octocrab::builder().build() {
-        Ok(obj) => obj,
-        Err(_e) => return Err(DownloaderError::NetworkError),
-    };
+/// A release with its status in `destination`, given the tools installed
+/// there and the names of the cached tarballs.
+fn build_release(
+    source: &'static Source,
+    release: &Release,
+    destination: Option<&Destination>,
+    installed: &[InstalledTool],
+    cached: &HashSet<String>,
+) -> Result<Proton, DownloaderError> {
+    let accepted = destination.is_some_and(|destination| destination.kind.accepts(source.kind));
+    let is_installed = accepted && installed.iter().any(|tool| tool.matches(&release.tag_name));
+    let mut proton = Proton::new(source, release.clone(), is_installed)?;
+    if !is_installed && cached.contains(&proton.get_tarball_name()) {
+        proton.set_status(proton_status::Downloaded)?;
+    }
+    Ok(proton)
+}
 
-    let releases = match octocrab
-        .repos("GloriousEggroll", "proton-ge-custom")
-        .releases()
-        .list()
-        .per_page(1)
-        .page(1 as u32)
-        .send()
-        .await
-    {
-        Ok(release) => release,
-        Err(_e) => return Err(DownloaderError::NetworkError),
-    };
+/// The most GitHub returns per page.
+const RELEASES_PER_PAGE: u8 = 100;
+
+async fn get_releases(
+    sources: Vec<String>,
+) -> Result<Vec<(&'static Source, Release)>, DownloaderError> {
+    let octocrab = http::github()?;
 
-    let mut retval: Vec<Release> = Vec::with_capacity(releases.items.len());
+    let mut retval: Vec<(&'static Source, Release)> = Vec::new();
 
-    for item in releases.items {
-        retval.push(item);
+    for source in sources.iter().filter_map(|id| source::find(id)) {
+        // The whole history, so older builds can be searched and installed.
+        for page in 1u32.. {
+            let releases = match octocrab
+                .repos(source.owner, source.repo)
+                .releases()
+                .list()
+                .per_page(RELEASES_PER_PAGE)
+                .page(page)
+                .send()
+                .await
+            {
+                Ok(releases) => releases.items,
+                Err(_e) => return Err(DownloaderError::NetworkError),
+            };
+            let last_page = releases.len() < usize::from(RELEASES_PER_PAGE);
+
+            // Drafts are only visible with push access and can't be installed.
+            for item in releases {
+                if source.channel(&item).is_some() {
+                    retval.push((source, item));
+                }
+            }
+            if last_page {
+                break;
+            }
+        }
     }
 
     Ok(retval)
 }
 
-#[derive(Debug, Clone)]
-enum DownloaderError {
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum DownloaderError {
+    #[error("{}", tr!("error-network"))]
     NetworkError,
+    #[error("{}", tr!("error-filesystem"))]
     FilesystemError,
+    #[error("{}", tr!("error-download"))]
     DownloadError,
+    #[error("{}", tr!("error-checksum"))]
+    ChecksumMismatch,
+    #[error("{}", tr!("error-insufficient-space", path = path.as_str(), needed = needed.as_str(), available = available.as_str()))]
+    InsufficientSpace {
+        path: String,
+        needed: String,
+        available: String,
+    },
+    #[error("{}", tr!("error-steam-running"))]
+    SteamRunning,
+    #[error("{}", tr!("error-steam-shutdown"))]
+    SteamShutdownFailed,
+    #[error("{}", tr!("error-steam-config"))]
+    SteamConfigError,
+    #[error("{}", tr!("error-settings", reason = _0.as_str()))]
+    SettingsError(String),
+    #[error("{}", tr!("error-not-writable", path = _0.as_str()))]
+    NotWritable(String),
+    #[error("{}", tr!("error-unsafe-archive"))]
+    UnsafeArchive,
+    #[error("{}", tr!("error-manifest-missing", path = _0.as_str()))]
+    ManifestMissing(String),
+    #[error("{}", tr!("error-proxy", reason = _0.as_str()))]
+    ProxyError(String),
+    #[error("{}", tr!("error-repair-unavailable"))]
+    RepairUnavailable,
+    #[error("{}", tr!("error-signature-missing"))]
+    SignatureMissing,
+    #[error("{}", tr!("error-signature-invalid"))]
+    SignatureInvalid,
+    #[error("{}", tr!("error-invalid-transition", from = _0.as_str(), to = _1.as_str()))]
+    InvalidTransition(String, String),
+    #[error("{}", tr!("error-timer", reason = _0.as_str()))]
+    TimerError(String),
 }
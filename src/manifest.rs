@@ -0,0 +1,272 @@
+use crate::DownloaderError;
+use sha2::{Digest, Sha512};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, copy, BufReader};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Component, Path, PathBuf};
+
+/// Written into every extracted tool folder. Launchers ignore it and it is
+/// skipped when looking for extra files.
+pub const MANIFEST_FILE: &str = ".proton_downloader-manifest";
+const HEADER: &str = "# proton_downloader manifest 1";
+/// Comment line naming the SHA-512 of the tarball the files came from.
+const TARBALL_PREFIX: &str = "# tarball ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Dir,
+    Symlink,
+}
+
+impl EntryKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EntryKind::File => "file",
+            EntryKind::Dir => "dir",
+            EntryKind::Symlink => "symlink",
+        }
+    }
+
+    fn parse(kind: &str) -> Option<EntryKind> {
+        match kind {
+            "file" => Some(EntryKind::File),
+            "dir" => Some(EntryKind::Dir),
+            "symlink" => Some(EntryKind::Symlink),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Relative to the tool folder.
+    pub path: PathBuf,
+    pub kind: EntryKind,
+    pub size: u64,
+    pub mode: u32,
+    /// SHA-512 of a file, the target of a symlink, empty for directories.
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub missing: Vec<PathBuf>,
+    pub modified: Vec<PathBuf>,
+    pub extra: Vec<PathBuf>,
+}
+
+impl Report {
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.modified.is_empty() && self.extra.is_empty()
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_clean() {
+            write!(f, "{}", tr!("report-intact"))
+        } else {
+            let report = tr!(
+                "report-changes",
+                missing = self.missing.len(),
+                modified = self.modified.len(),
+                extra = self.extra.len()
+            );
+            write!(f, "{}", report)
+        }
+    }
+}
+
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha512::new();
+    copy(&mut BufReader::new(File::open(path)?), &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Describes what is on disk at `root/path` right now.
+fn describe(root: &Path, path: &Path) -> io::Result<ManifestEntry> {
+    let full = root.join(path);
+    let metadata = full.symlink_metadata()?;
+    let (kind, hash) = if metadata.file_type().is_symlink() {
+        let target = fs::read_link(&full)?;
+        (EntryKind::Symlink, target.to_string_lossy().into_owned())
+    } else if metadata.is_dir() {
+        (EntryKind::Dir, String::new())
+    } else {
+        (EntryKind::File, hash_file(&full)?)
+    };
+
+    Ok(ManifestEntry {
+        path: path.to_path_buf(),
+        kind,
+        size: if kind == EntryKind::File {
+            metadata.len()
+        } else {
+            0
+        },
+        mode: metadata.permissions().mode() & 0o7777,
+        hash,
+    })
+}
+
+/// Drops `./` components so paths from the archive compare equal to the
+/// ones found when walking the folder.
+pub fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .collect()
+}
+
+/// Writes a manifest into each top level folder of an extracted archive.
+/// `paths` are the archive entries, relative to `target`, and `tarball` the
+/// hash of the archive when it is known.
+pub fn record(target: &Path, paths: &[PathBuf], tarball: Option<&str>) -> io::Result<()> {
+    let mut tools: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+    for path in paths {
+        let path = normalize(path);
+        let mut components = path.components();
+        if let Some(Component::Normal(folder)) = components.next() {
+            // Archives don't always list the directories leading to a file.
+            let listed = tools.entry(PathBuf::from(folder)).or_default();
+            for inner in components.as_path().ancestors() {
+                if !inner.as_os_str().is_empty() {
+                    listed.push(inner.to_path_buf());
+                }
+            }
+        }
+    }
+
+    for (folder, mut paths) in tools {
+        let root = target.join(folder);
+        if !root.is_dir() {
+            continue;
+        }
+        paths.sort();
+        paths.dedup();
+
+        let mut entries = Vec::with_capacity(paths.len());
+        for path in paths {
+            entries.push(describe(&root, &path)?);
+        }
+        write(&root, &entries, tarball)?;
+    }
+    Ok(())
+}
+
+fn write(root: &Path, entries: &[ManifestEntry], tarball: Option<&str>) -> io::Result<()> {
+    let mut content = String::from(HEADER);
+    content.push('\n');
+    if let Some(hash) = tarball {
+        content.push_str(&format!("{}{}\n", TARBALL_PREFIX, hash));
+    }
+    for entry in entries {
+        // Tabs and newlines would break the line format, no release has them.
+        let path = entry.path.to_string_lossy();
+        if path.contains(['\t', '\n']) {
+            continue;
+        }
+        content.push_str(&format!(
+            "{}\t{:o}\t{}\t{}\t{}\n",
+            entry.kind.as_str(),
+            entry.mode,
+            entry.size,
+            entry.hash,
+            path
+        ));
+    }
+    fs::write(root.join(MANIFEST_FILE), content)
+}
+
+pub fn read(root: &Path) -> Result<Vec<ManifestEntry>, DownloaderError> {
+    let content = fs::read_to_string(root.join(MANIFEST_FILE)).or(Err(
+        DownloaderError::ManifestMissing(root.display().to_string()),
+    ))?;
+
+    let mut lines = content.lines();
+    if lines.next() != Some(HEADER) {
+        return Err(DownloaderError::ManifestMissing(root.display().to_string()));
+    }
+
+    let mut entries = Vec::new();
+    for line in lines.filter(|line| !line.starts_with('#')) {
+        let fields: Vec<&str> = line.splitn(5, '\t').collect();
+        let entry = match fields[..] {
+            [kind, mode, size, hash, path] => EntryKind::parse(kind).and_then(|kind| {
+                Some(ManifestEntry {
+                    path: PathBuf::from(path),
+                    kind,
+                    size: size.parse().ok()?,
+                    mode: u32::from_str_radix(mode, 8).ok()?,
+                    hash: hash.to_string(),
+                })
+            }),
+            _ => None,
+        };
+        match entry {
+            Some(entry) => entries.push(entry),
+            None => return Err(DownloaderError::ManifestMissing(root.display().to_string())),
+        }
+    }
+    Ok(entries)
+}
+
+/// Hash of the tarball an installed tool was extracted from.
+pub fn tarball_hash(root: &Path) -> Option<String> {
+    let content = fs::read_to_string(root.join(MANIFEST_FILE)).ok()?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix(TARBALL_PREFIX))
+        .map(String::from)
+}
+
+/// Compares an installed tool folder against the manifest written when it
+/// was extracted.
+pub fn verify(root: &Path) -> Result<Report, DownloaderError> {
+    let entries = read(root)?;
+    let mut report = Report::default();
+
+    for entry in &entries {
+        match describe(root, &entry.path) {
+            Ok(current) if current == *entry => {}
+            Ok(_) => report.modified.push(entry.path.clone()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                report.missing.push(entry.path.clone())
+            }
+            Err(_) => report.modified.push(entry.path.clone()),
+        }
+    }
+
+    let known: HashSet<&Path> = entries.iter().map(|entry| entry.path.as_path()).collect();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(dir) = pending.pop() {
+        let read_dir = fs::read_dir(root.join(&dir)).or(Err(DownloaderError::FilesystemError))?;
+        for item in read_dir {
+            let item = item.or(Err(DownloaderError::FilesystemError))?;
+            let path = dir.join(item.file_name());
+            if dir.as_os_str().is_empty() && item.file_name() == MANIFEST_FILE {
+                continue;
+            }
+            if !known.contains(path.as_path()) {
+                report.extra.push(path);
+                continue;
+            }
+            // Symlinked directories are not followed out of the tool folder.
+            let is_dir = item
+                .file_type()
+                .map(|file_type| file_type.is_dir())
+                .unwrap_or(false);
+            if is_dir {
+                pending.push(path);
+            }
+        }
+    }
+
+    report.extra.sort();
+    Ok(report)
+}
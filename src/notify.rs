@@ -0,0 +1,397 @@
+use iced::futures::channel::mpsc;
+use iced::futures::future;
+use iced::futures::stream::{self, BoxStream, StreamExt};
+use iced::subscription;
+use std::collections::{HashMap, HashSet};
+use zbus::zvariant::Value;
+use zbus::{dbus_proxy, Connection, MatchRule, MessageStream, MessageType};
+
+const INTERFACE: &str = "org.freedesktop.Notifications";
+const PATH: &str = "/org/freedesktop/Notifications";
+const APP_NAME: &str = "Proton Downloader";
+const DESKTOP_ENTRY: &str = "proton_downloader";
+/// Urgency levels of the notification spec.
+const NORMAL: u8 = 1;
+const CRITICAL: u8 = 2;
+
+#[dbus_proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+
+    fn get_capabilities(&self) -> zbus::Result<Vec<String>>;
+
+    #[dbus_proxy(signal)]
+    fn action_invoked(&self, id: u32, action_key: &str) -> zbus::Result<()>;
+
+    #[dbus_proxy(signal)]
+    fn notification_closed(&self, id: u32, reason: u32) -> zbus::Result<()>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+    /// Action keys and their button labels.
+    pub actions: Vec<(String, String)>,
+    pub critical: bool,
+}
+
+impl Notification {
+    pub fn new(summary: impl ToString, body: impl ToString) -> Self {
+        Notification {
+            summary: summary.to_string(),
+            body: body.to_string(),
+            actions: Vec::new(),
+            critical: false,
+        }
+    }
+
+    pub fn action(mut self, key: impl ToString, label: impl ToString) -> Self {
+        self.actions.push((key.to_string(), label.to_string()));
+        self
+    }
+
+    pub fn critical(mut self) -> Self {
+        self.critical = true;
+        self
+    }
+}
+
+/// Connection to the notification daemon of a session bus.
+pub struct Client {
+    connection: Connection,
+    proxy: NotificationsProxy<'static>,
+    supports_actions: bool,
+}
+
+impl Client {
+    /// Connects to the bus at `address`, or the session bus when `None`.
+    /// Fails when no notification daemon is running or can be activated.
+    pub async fn connect(address: Option<&str>) -> zbus::Result<Client> {
+        let connection = match address {
+            Some(address) => zbus::ConnectionBuilder::address(address)?.build().await?,
+            None => Connection::session().await?,
+        };
+        let proxy = NotificationsProxy::new(&connection).await?;
+        let capabilities = proxy.get_capabilities().await?;
+        Ok(Client {
+            connection,
+            proxy,
+            supports_actions: capabilities.iter().any(|name| name == "actions"),
+        })
+    }
+
+    /// Shows `notification` and returns its id. Actions are left out when
+    /// the daemon can't show them.
+    pub async fn send(&self, notification: &Notification) -> zbus::Result<u32> {
+        let actions: Vec<&str> = if self.supports_actions {
+            notification
+                .actions
+                .iter()
+                .flat_map(|(key, label)| [key.as_str(), label.as_str()])
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let urgency = if notification.critical {
+            CRITICAL
+        } else {
+            NORMAL
+        };
+        let hints = HashMap::from([
+            ("urgency", Value::from(urgency)),
+            ("desktop-entry", Value::from(DESKTOP_ENTRY)),
+        ]);
+        self.proxy
+            .notify(
+                APP_NAME,
+                0,
+                "",
+                &notification.summary,
+                &notification.body,
+                &actions,
+                hints,
+                -1,
+            )
+            .await
+    }
+
+    /// Notification ids and action keys of clicked actions, and closed
+    /// notifications with an empty key. Both signals come through one stream
+    /// so they arrive in the order the daemon sent them, a click is never
+    /// seen after the close that follows it.
+    pub async fn actions(&self) -> zbus::Result<BoxStream<'static, (u32, String)>> {
+        let rule = MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .interface(INTERFACE)?
+            .path(PATH)?
+            .build();
+        let messages = MessageStream::for_match_rule(rule, &self.connection, None).await?;
+        Ok(messages
+            .filter_map(|message| async move {
+                let message = message.ok()?;
+                if let Some(signal) = ActionInvoked::from_message(message.clone()) {
+                    let args = signal.args().ok()?;
+                    return Some((args.id, args.action_key.to_string()));
+                }
+                let signal = NotificationClosed::from_message(message)?;
+                Some((signal.args().ok()?.id, String::new()))
+            })
+            .boxed())
+    }
+}
+
+/// Hands notifications to the running [`notifications`] subscription.
+#[derive(Debug, Clone)]
+pub struct Notifier(mpsc::Sender<Notification>);
+
+impl Notifier {
+    /// Notifications that don't fit in the queue are dropped.
+    pub fn send(&self, notification: Notification) {
+        let _ = self.0.clone().try_send(notification);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    Ready(Notifier),
+    /// Key of an action the user picked in one of our notifications.
+    Action(String),
+}
+
+enum Input {
+    Send(Notification),
+    Action(u32, String),
+}
+
+enum State {
+    Starting,
+    Running {
+        client: Client,
+        inputs: BoxStream<'static, Input>,
+        /// Ids of shown notifications, other clients' actions are ignored.
+        shown: HashSet<u32>,
+    },
+}
+
+/// Connects to the session bus once and then keeps sending notifications and
+/// reporting their actions. Without a notification daemon it stays silent.
+pub fn notifications() -> iced::Subscription<Event> {
+    subscription::unfold("notifications", State::Starting, run)
+}
+
+async fn run(state: State) -> (Event, State) {
+    match state {
+        State::Starting => {
+            let connected = match Client::connect(None).await {
+                Ok(client) => client.actions().await.map(|actions| (client, actions)),
+                Err(e) => Err(e),
+            };
+            let (client, actions) = match connected {
+                Ok(connected) => connected,
+                Err(e) => {
+                    tracing::info!("desktop notifications unavailable: {}", e);
+                    return future::pending().await;
+                }
+            };
+            let (sender, receiver) = mpsc::channel(16);
+            let inputs = stream::select(
+                receiver.map(Input::Send),
+                actions.map(|(id, key)| Input::Action(id, key)),
+            )
+            .boxed();
+            (
+                Event::Ready(Notifier(sender)),
+                State::Running {
+                    client,
+                    inputs,
+                    shown: HashSet::new(),
+                },
+            )
+        }
+        State::Running {
+            client,
+            mut inputs,
+            mut shown,
+        } => loop {
+            match inputs.next().await {
+                Some(Input::Send(notification)) => match client.send(&notification).await {
+                    Ok(id) => {
+                        shown.insert(id);
+                    }
+                    Err(e) => tracing::warn!("failed to show notification: {}", e),
+                },
+                Some(Input::Action(id, key)) if key.is_empty() => {
+                    shown.remove(&id);
+                }
+                Some(Input::Action(id, key)) if shown.contains(&id) => {
+                    let state = State::Running {
+                        client,
+                        inputs,
+                        shown,
+                    };
+                    return (Event::Action(key), state);
+                }
+                Some(Input::Action(..)) => {}
+                // The bus connection is gone, there is nothing left to do.
+                None => return future::pending().await,
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use std::time::Duration;
+    use zbus::zvariant::OwnedValue;
+    use zbus::{dbus_interface, ConnectionBuilder, SignalContext};
+
+    /// A private session bus, stopped on drop.
+    struct Bus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl Bus {
+        fn start() -> Option<Bus> {
+            let mut daemon = Command::new("dbus-daemon")
+                .args(["--session", "--print-address", "--nofork"])
+                .stdout(Stdio::piped())
+                .spawn()
+                .ok()?;
+            let mut address = String::new();
+            BufReader::new(daemon.stdout.take()?)
+                .read_line(&mut address)
+                .ok()?;
+            Some(Bus {
+                daemon,
+                address: address.trim().to_string(),
+            })
+        }
+    }
+
+    impl Drop for Bus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    /// Stands in for the notification daemon and remembers what was shown.
+    #[derive(Default)]
+    struct Daemon {
+        shown: Vec<(String, Vec<String>)>,
+    }
+
+    #[dbus_interface(name = "org.freedesktop.Notifications")]
+    impl Daemon {
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &mut self,
+            _app_name: String,
+            _replaces_id: u32,
+            _app_icon: String,
+            summary: String,
+            _body: String,
+            actions: Vec<String>,
+            _hints: HashMap<String, OwnedValue>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            self.shown.push((summary, actions));
+            self.shown.len() as u32
+        }
+
+        fn get_capabilities(&self) -> Vec<String> {
+            vec!["actions".to_string()]
+        }
+
+        #[dbus_interface(signal)]
+        async fn action_invoked(
+            context: &SignalContext<'_>,
+            id: u32,
+            action_key: &str,
+        ) -> zbus::Result<()>;
+
+        #[dbus_interface(signal)]
+        async fn notification_closed(
+            context: &SignalContext<'_>,
+            id: u32,
+            reason: u32,
+        ) -> zbus::Result<()>;
+    }
+
+    #[tokio::test]
+    async fn sends_and_reports_actions_in_order() {
+        let Some(bus) = Bus::start() else {
+            eprintln!("dbus-daemon not available, skipping");
+            return;
+        };
+        let server = ConnectionBuilder::address(bus.address.as_str())
+            .unwrap()
+            .name(INTERFACE)
+            .unwrap()
+            .serve_at(PATH, Daemon::default())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let client = Client::connect(Some(&bus.address)).await.unwrap();
+        assert!(client.supports_actions);
+        let mut actions = client.actions().await.unwrap();
+        let notification = Notification::new("GE-Proton9-4", "available")
+            .action("install:GE-Proton9-4", "Install");
+        let id = client.send(&notification).await.unwrap();
+
+        let daemon = server
+            .object_server()
+            .interface::<_, Daemon>(PATH)
+            .await
+            .unwrap();
+        assert_eq!(
+            daemon.get().await.shown,
+            [(
+                "GE-Proton9-4".to_string(),
+                vec!["install:GE-Proton9-4".to_string(), "Install".to_string()]
+            )]
+        );
+
+        // Daemons close a notification right after its action was clicked.
+        Daemon::action_invoked(daemon.signal_context(), id, "install:GE-Proton9-4")
+            .await
+            .unwrap();
+        Daemon::notification_closed(daemon.signal_context(), id, 2)
+            .await
+            .unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(5), async {
+            [actions.next().await, actions.next().await]
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            received,
+            [
+                Some((id, "install:GE-Proton9-4".to_string())),
+                Some((id, String::new()))
+            ]
+        );
+    }
+}
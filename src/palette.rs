@@ -0,0 +1,176 @@
+use iced::keyboard::{KeyCode, Modifiers};
+
+/// Something the keyboard or the command palette can do in the release list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Next,
+    Previous,
+    First,
+    Last,
+    Install,
+    Remove,
+    Verify,
+    Refresh,
+    Search,
+    OpenPalette,
+    /// Closes the palette, or leaves the current screen.
+    Close,
+    InstallFromFile,
+    TogglePrereleases,
+    ShowSettings,
+    ShowCache,
+    ShowLog,
+    /// Selects the release with this key.
+    Go(String),
+}
+
+impl Action {
+    /// Actions listed in the palette, releases are added by the caller.
+    pub const COMMANDS: [Action; 10] = [
+        Action::Install,
+        Action::Remove,
+        Action::Verify,
+        Action::Refresh,
+        Action::Search,
+        Action::InstallFromFile,
+        Action::TogglePrereleases,
+        Action::ShowSettings,
+        Action::ShowCache,
+        Action::ShowLog,
+    ];
+
+    pub fn label(&self) -> String {
+        match self {
+            Action::Next => tr!("action-next"),
+            Action::Previous => tr!("action-previous"),
+            Action::First => tr!("action-first"),
+            Action::Last => tr!("action-last"),
+            Action::Install => tr!("action-install"),
+            Action::Remove => tr!("action-remove"),
+            Action::Verify => tr!("action-verify"),
+            Action::Refresh => tr!("action-refresh"),
+            Action::Search => tr!("action-search"),
+            Action::OpenPalette => tr!("action-palette"),
+            Action::Close => tr!("action-close"),
+            Action::InstallFromFile => tr!("install-from-file"),
+            Action::TogglePrereleases => tr!("action-toggle-prereleases"),
+            Action::ShowSettings => tr!("settings"),
+            Action::ShowCache => tr!("cache"),
+            Action::ShowLog => tr!("log"),
+            Action::Go(key) => key.clone(),
+        }
+    }
+
+    /// Key that triggers the action, shown next to it in the palette.
+    pub fn shortcut(&self) -> &'static str {
+        match self {
+            Action::Next => "↓",
+            Action::Previous => "↑",
+            Action::First => "Home",
+            Action::Last => "End",
+            Action::Install => "Enter",
+            Action::Remove => "Delete",
+            Action::Verify => "V",
+            Action::Refresh => "F5",
+            Action::Search => "/",
+            Action::OpenPalette => "Ctrl+K",
+            Action::Close => "Esc",
+            Action::InstallFromFile => "Ctrl+O",
+            Action::TogglePrereleases => "P",
+            Action::ShowSettings => "Ctrl+,",
+            Action::ShowCache | Action::ShowLog | Action::Go(_) => "",
+        }
+    }
+}
+
+/// Maps a key press to an action. `captured` is set when a widget, usually
+/// a focused text input, already handled the key, then only the keys that
+/// leave it still count.
+pub fn shortcut(key: KeyCode, modifiers: Modifiers, captured: bool) -> Option<Action> {
+    let action = match (key, modifiers.command()) {
+        (KeyCode::K, true) => Action::OpenPalette,
+        (KeyCode::Escape, _) => Action::Close,
+        _ if captured => return None,
+        (KeyCode::R, true) => Action::Refresh,
+        (KeyCode::F, true) | (KeyCode::Slash, false) => Action::Search,
+        (KeyCode::O, true) => Action::InstallFromFile,
+        (KeyCode::Comma, true) => Action::ShowSettings,
+        (_, true) => return None,
+        (KeyCode::Down | KeyCode::J, false) => Action::Next,
+        (KeyCode::Up | KeyCode::K, false) => Action::Previous,
+        (KeyCode::Home, false) => Action::First,
+        (KeyCode::End, false) => Action::Last,
+        (KeyCode::Enter | KeyCode::NumpadEnter | KeyCode::I, false) => Action::Install,
+        (KeyCode::Delete, false) => Action::Remove,
+        (KeyCode::V, false) => Action::Verify,
+        (KeyCode::F5, false) => Action::Refresh,
+        (KeyCode::P, false) => Action::TogglePrereleases,
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// State of the open command palette.
+#[derive(Debug, Clone, Default)]
+pub struct Palette {
+    pub query: String,
+    /// Position of the highlighted entry among the matches.
+    pub highlighted: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub action: Action,
+    pub label: String,
+    /// Shortcut or, for releases, their source.
+    pub hint: String,
+}
+
+impl Entry {
+    pub fn new(action: Action) -> Entry {
+        Entry {
+            label: action.label(),
+            hint: action.shortcut().to_string(),
+            action,
+        }
+    }
+}
+
+/// Entries matching `query`, best match first. Ties keep the given order.
+pub fn filter(entries: Vec<Entry>, query: &str) -> Vec<Entry> {
+    if query.trim().is_empty() {
+        return entries;
+    }
+    let mut scored: Vec<(i32, Entry)> = entries
+        .into_iter()
+        .filter_map(|entry| Some((score(query, &entry.label)?, entry)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Fuzzy match of `query` against `candidate`: every character of the
+/// query has to appear in order, ignoring case. Runs of consecutive
+/// characters and matches at the start of words score higher, long
+/// candidates a little lower.
+pub fn score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.chars().filter(|c| !c.is_whitespace()) {
+        let wanted = wanted.to_lowercase().next()?;
+        let found = (position..candidate.len())
+            .find(|&index| candidate[index].to_lowercase().next() == Some(wanted))?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score * 10 - candidate.len() as i32)
+}
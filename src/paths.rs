@@ -0,0 +1,38 @@
+use std::path::{Path, PathBuf};
+
+const APP_NAME: &str = "proton_downloader";
+
+/// Resolves an XDG base directory for this application, ignoring relative
+/// values as the spec requires.
+fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    let base = match std::env::var_os(var) {
+        Some(dir) if Path::new(&dir).is_absolute() => PathBuf::from(dir),
+        _ => home::home_dir()?.join(fallback),
+    };
+    Some(base.join(APP_NAME))
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+pub fn cache_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CACHE_HOME", ".cache")
+}
+
+pub fn state_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_STATE_HOME", ".local/state")
+}
+
+pub fn log_dir() -> Option<PathBuf> {
+    Some(state_dir()?.join("logs"))
+}
+
+/// Where systemd looks for user units, outside of the application folder.
+pub fn systemd_user_dir() -> Option<PathBuf> {
+    Some(
+        xdg_dir("XDG_CONFIG_HOME", ".config")?
+            .parent()?
+            .join("systemd/user"),
+    )
+}
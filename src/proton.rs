@@ -1,3 +1,7 @@
+use crate::http;
+use crate::signature::SignatureKind;
+use crate::source::{Channel, Source};
+use crate::version::Version;
 use crate::DownloaderError;
 use crate::Message;
 use bytes::{Buf, Bytes};
@@ -15,62 +19,122 @@ use regex::Regex;
 use sha2::{Digest, Sha512};
 use tar::Archive;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum proton_status {
     Installed,
     Uninstalled,
+    /// The verified tarball is in the cache but not extracted.
     Downloaded,
     Installing,
+    /// Also covers installs waiting in the queue.
     Downloading,
 }
+
+impl proton_status {
+    /// Whether a release may go from this status to `next`. Staying in the
+    /// same status is always allowed.
+    pub fn can_become(&self, next: proton_status) -> bool {
+        use proton_status::*;
+        *self == next
+            || matches!(
+                (self, next),
+                (Uninstalled, Downloading | Downloaded | Installed)
+                    | (Downloaded, Downloading | Uninstalled | Installed)
+                    | (Downloading, Installing | Uninstalled | Downloaded)
+                    | (Installing, Installed | Uninstalled | Downloaded)
+                    | (Installed, Uninstalled)
+            )
+    }
+}
+
+impl std::fmt::Display for proton_status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            proton_status::Installed => write!(f, "{}", tr!("badge-installed")),
+            proton_status::Uninstalled => write!(f, "{}", tr!("badge-uninstalled")),
+            proton_status::Downloaded => write!(f, "{}", tr!("badge-downloaded")),
+            proton_status::Installing => write!(f, "{}", tr!("badge-installing")),
+            proton_status::Downloading => write!(f, "{}", tr!("badge-downloading")),
+        }
+    }
+}
 #[derive(Debug, Clone)]
 pub struct Proton {
+    source: &'static Source,
     status: proton_status,
     release: Release,
+    channel: Channel,
+    version: Option<Version>,
     tarball: Option<Bytes>,
     tarball_url: String,
+    tarball_size: u64,
     checksum_url: String,
+    signature_url: Option<String>,
+    /// Name of the key whose signature was verified on install.
+    signed_by: Option<String>,
 }
 
-fn get_proton_urls(release: &Release) -> Result<(String, String), DownloaderError> {
+fn get_proton_urls(release: &Release) -> Result<(String, String, u64), DownloaderError> {
     let mut checksum_url: String = String::new();
     let mut tarball_url: String = String::new();
+    let mut tarball_size: u64 = 0;
     let mut checksum_found: bool = false;
     let mut tarball_found: bool = false;
     let checksum_re = Regex::new(r"\.sha512sum$").unwrap();
-    let tarball_re = Regex::new(r"\.tar\.gz$").unwrap();
+    let tarball_re = Regex::new(r"\.tar\.(gz|xz)$").unwrap();
 
     for item in &release.assets {
         let browser_url = &item.browser_download_url;
-
-        let url: String = match browser_url.host_str() {
-            Some(host) => String::from(host),
-            None => String::new(),
-        };
-
-        let url = format!("http://{}{}", url, browser_url.path());
+        // Kept as published, over https and through an https proxy.
+        let url = browser_url.to_string();
 
         if checksum_re.is_match(browser_url.path()) {
             checksum_url = url;
             checksum_found = true;
         } else if tarball_re.is_match(browser_url.path()) {
             tarball_url = url;
+            tarball_size = item.size.max(0) as u64;
             tarball_found = true;
         }
     }
 
     if tarball_found && checksum_found {
-        Ok((tarball_url, checksum_url))
+        Ok((tarball_url, checksum_url, tarball_size))
     } else {
         Err(DownloaderError::DownloadError)
     }
 }
 
+/// Detached signature published for the tarball, e.g. `<tarball>.minisig`.
+fn get_signature_url(release: &Release, tarball_url: &str) -> Option<String> {
+    let tarball_name = tarball_url.rsplit('/').next()?;
+    release
+        .assets
+        .iter()
+        .find(|item| {
+            item.name.starts_with(tarball_name)
+                && SignatureKind::from_asset(&item.name[tarball_name.len()..]).is_some()
+        })
+        .map(|item| item.browser_download_url.to_string())
+}
+
+pub fn release_key(source: &Source, release: &Release) -> String {
+    format!("{}/{}", source.id, release.tag_name)
+}
+
 impl Proton {
-    pub fn new(release: Release, installed: bool) -> Result<Proton, DownloaderError> {
-        println!("creating new proton");
-        let (tar_url, check_url) = get_proton_urls(&release)?;
+    pub fn new(
+        source: &'static Source,
+        release: Release,
+        installed: bool,
+    ) -> Result<Proton, DownloaderError> {
+        tracing::debug!("creating new proton");
+        let (tar_url, check_url, tar_size) = get_proton_urls(&release)?;
+        let signature_url = get_signature_url(&release, &tar_url);
         Ok(Self {
+            source,
+            channel: source.channel(&release).unwrap_or(Channel::Stable),
+            version: Version::parse(&release.tag_name),
             release,
             status: if installed {
                 proton_status::Installed
@@ -79,7 +143,10 @@ impl Proton {
             },
             tarball: None,
             tarball_url: tar_url,
+            tarball_size: tar_size,
             checksum_url: check_url,
+            signature_url,
+            signed_by: None,
         })
     }
 
@@ -87,10 +154,40 @@ impl Proton {
         self.tarball_url.clone()
     }
 
+    /// Unix timestamp of the publication.
+    pub fn get_published_at(&self) -> Option<i64> {
+        self.release.published_at.map(|date| date.timestamp())
+    }
+
+    pub fn get_tarball_size(&self) -> u64 {
+        self.tarball_size
+    }
+
+    /// File name of the tarball, which is what the cache knows it by.
+    pub fn get_tarball_name(&self) -> String {
+        self.tarball_url
+            .rsplit('/')
+            .next()
+            .unwrap_or(&self.tarball_url)
+            .to_string()
+    }
+
     pub fn get_checksum_url(&self) -> String {
         self.checksum_url.clone()
     }
 
+    pub fn get_signature_url(&self) -> Option<String> {
+        self.signature_url.clone()
+    }
+
+    pub fn get_signed_by(&self) -> Option<&str> {
+        self.signed_by.as_deref()
+    }
+
+    pub fn set_signed_by(&mut self, name: String) {
+        self.signed_by = Some(name);
+    }
+
     pub async fn install(&mut self) -> Result<(), DownloaderError> {
         self.download().await?;
 
@@ -112,12 +209,12 @@ impl Proton {
 
     async fn download(&mut self) -> Result<String, DownloaderError> {
         self.status = proton_status::Downloading;
-        let (tarball_url, checksum_url) = get_proton_urls(&self.release)?;
+        let (tarball_url, checksum_url, _) = get_proton_urls(&self.release)?;
 
-        println!("tarball url: {}", tarball_url);
-        println!("checksum url: {}", checksum_url);
+        tracing::debug!("tarball url: {}", tarball_url);
+        tracing::debug!("checksum url: {}", checksum_url);
 
-        let response = reqwest::get(&checksum_url)
+        let response = http::get(&checksum_url)
             .await
             .or(Err(DownloaderError::DownloadError))?;
 
@@ -128,7 +225,7 @@ impl Proton {
 
         let checksum = content;
 
-        let response = reqwest::get(&tarball_url)
+        let response = http::get(&tarball_url)
             .await
             .or(Err(DownloaderError::DownloadError))?;
 
@@ -138,7 +235,7 @@ impl Proton {
             .or(Err(DownloaderError::DownloadError))?;
 
         let checksum: Vec<&str> = checksum.split(' ').collect();
-        println!("checksum: {}", checksum[0]);
+        tracing::debug!("checksum: {}", checksum[0]);
 
         let mut hasher = Sha512::new();
 
@@ -152,10 +249,10 @@ impl Proton {
         }
 
         if caluclated_checksum.eq(&checksum[0]) {
-            println!("Checksum matches!!!");
+            tracing::info!("checksum matches");
         }
 
-        println!("calculated checksum: {}", caluclated_checksum);
+        tracing::debug!("calculated checksum: {}", caluclated_checksum);
 
         self.tarball = Some(content);
         Ok(self.release.tag_name.clone())
@@ -165,6 +262,34 @@ impl Proton {
         &self.status
     }
 
+    pub fn set_status(&mut self, status: proton_status) -> Result<(), DownloaderError> {
+        if !self.status.can_become(status) {
+            return Err(DownloaderError::InvalidTransition(
+                self.status.to_string(),
+                status.to_string(),
+            ));
+        }
+        self.status = status;
+        Ok(())
+    }
+
+    pub fn get_source(&self) -> &'static Source {
+        self.source
+    }
+
+    pub fn get_channel(&self) -> Channel {
+        self.channel
+    }
+
+    pub fn get_version(&self) -> Option<&Version> {
+        self.version.as_ref()
+    }
+
+    /// Unique across sources, GE-Proton and Wine-GE share their tag names.
+    pub fn get_key(&self) -> String {
+        release_key(self.source, &self.release)
+    }
+
     pub fn get_name(&self) -> String {
         self.release.tag_name.clone()
     }
@@ -1,79 +1,302 @@
+use crate::proton::proton_status;
 use iced::{
     advanced::{
-        layout, renderer,
-        widget::{self, Widget},
+        layout, mouse, overlay, renderer,
+        widget::{Operation, Tree, Widget},
+        Clipboard, Layout, Shell,
     },
-    Color, Element, Length, Size,
+    event, theme,
+    widget::{container, horizontal_space, progress_bar, text},
+    Alignment, Color, Element, Event, Length, Padding, Rectangle, Theme,
 };
 
-pub struct ProtonWidget {
-    name: String,
-    installed: bool,
+/// A release in the list: name, version and a status badge on the left, then
+/// any details, a progress bar and the action buttons. The card highlights
+/// on hover and can be clicked to select it.
+pub struct ProtonWidget<'a, Message> {
+    children: Vec<Element<'a, Message>>,
+    status: proton_status,
+    /// Position of the spacer separating the description from the actions.
+    spacer: usize,
+    selected: bool,
+    on_press: Option<Message>,
 }
 
-enum states {
-    Installed,
-    Uninstalled,
-    Installing,
+/// Position of the badge among the children.
+const BADGE: usize = 2;
+
+pub fn proton_widget<'a, Message: 'a>(
+    name: impl ToString,
+    version: impl ToString,
+    status: proton_status,
+) -> ProtonWidget<'a, Message> {
+    ProtonWidget::new(name, version, status)
 }
 
-pub fn proton_widget(name: String, installed: bool) -> ProtonWidget {
-    ProtonWidget::new(name, installed)
+impl<'a, Message: 'a> ProtonWidget<'a, Message> {
+    pub fn new(name: impl ToString, version: impl ToString, status: proton_status) -> Self {
+        let badge = container(text(status.to_string()).size(14)).padding([1, 8]);
+        Self {
+            children: vec![
+                text(name.to_string()).into(),
+                text(version.to_string()).into(),
+                badge.into(),
+                horizontal_space(Length::Fill).into(),
+            ],
+            status,
+            spacer: 3,
+            selected: false,
+            on_press: None,
+        }
+    }
+
+    /// Adds a line of text after the badge, empty ones are skipped.
+    pub fn detail(mut self, detail: impl ToString) -> Self {
+        let detail = detail.to_string();
+        if !detail.is_empty() {
+            self.children.insert(self.spacer, text(detail).into());
+            self.spacer += 1;
+        }
+        self
+    }
+
+    /// Percentage shown in a bar before the actions.
+    pub fn progress(mut self, percentage: f32) -> Self {
+        self.children.push(
+            progress_bar(0.0..=100.0, percentage)
+                .width(Length::Fixed(150.0))
+                .into(),
+        );
+        self
+    }
+
+    /// Appends an action, usually a button, at the right end of the card.
+    pub fn push(mut self, action: impl Into<Element<'a, Message>>) -> Self {
+        self.children.push(action.into());
+        self
+    }
+
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Sent when the card is clicked outside of its actions.
+    pub fn on_press(mut self, message: Message) -> Self {
+        self.on_press = Some(message);
+        self
+    }
+
+    fn badge_colors(&self, theme: &Theme) -> theme::palette::Pair {
+        let palette = theme.extended_palette();
+        match self.status {
+            proton_status::Installed => palette.success.base,
+            proton_status::Downloading | proton_status::Installing => palette.primary.base,
+            proton_status::Downloaded => palette.secondary.base,
+            proton_status::Uninstalled => palette.background.strong,
+        }
+    }
 }
 
-impl<Message, Renderer> Widget<Message, Renderer> for ProtonWidget
-where
-    Renderer: renderer::Renderer,
-{
-    fn width(&self) -> iced::Length {
-        Length::Shrink
+impl<'a, Message: Clone> Widget<Message, iced::Renderer> for ProtonWidget<'a, Message> {
+    fn children(&self) -> Vec<Tree> {
+        self.children.iter().map(Tree::new).collect()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(&self.children)
     }
 
-    fn height(&self) -> iced::Length {
+    fn width(&self) -> Length {
+        Length::Fill
+    }
+
+    fn height(&self) -> Length {
         Length::Shrink
     }
 
-    fn layout(
+    fn layout(&self, renderer: &iced::Renderer, limits: &layout::Limits) -> layout::Node {
+        let limits = limits.width(Length::Fill).height(Length::Shrink);
+        layout::flex::resolve(
+            layout::flex::Axis::Horizontal,
+            renderer,
+            &limits,
+            Padding::from([4, 8]),
+            10.0,
+            Alignment::Center,
+            &self.children,
+        )
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &iced::Renderer,
+        operation: &mut dyn Operation<Message>,
+    ) {
+        operation.container(None, layout.bounds(), &mut |operation| {
+            self.children
+                .iter()
+                .zip(&mut tree.children)
+                .zip(layout.children())
+                .for_each(|((child, state), layout)| {
+                    child
+                        .as_widget()
+                        .operate(state, layout, renderer, operation);
+                })
+        });
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &iced::Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        let status = self
+            .children
+            .iter_mut()
+            .zip(&mut tree.children)
+            .zip(layout.children())
+            .map(|((child, state), layout)| {
+                child.as_widget_mut().on_event(
+                    state,
+                    event.clone(),
+                    layout,
+                    cursor,
+                    renderer,
+                    clipboard,
+                    shell,
+                    viewport,
+                )
+            })
+            .fold(event::Status::Ignored, event::Status::merge);
+
+        if status == event::Status::Captured {
+            return status;
+        }
+        match (&event, &self.on_press) {
+            (Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)), Some(message))
+                if cursor.is_over(layout.bounds()) =>
+            {
+                shell.publish(message.clone());
+                event::Status::Captured
+            }
+            _ => event::Status::Ignored,
+        }
+    }
+
+    fn mouse_interaction(
         &self,
-        _renderer: &Renderer,
-        _limits: &iced::advanced::layout::Limits,
-    ) -> iced::advanced::layout::Node {
-        layout::Node::new(Size::new(100.0, 100.0))
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &iced::Renderer,
+    ) -> mouse::Interaction {
+        let interaction = self
+            .children
+            .iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+            .map(|((child, state), layout)| {
+                child
+                    .as_widget()
+                    .mouse_interaction(state, layout, cursor, viewport, renderer)
+            })
+            .max()
+            .unwrap_or_default();
+
+        if interaction == mouse::Interaction::Idle
+            && self.on_press.is_some()
+            && cursor.is_over(layout.bounds())
+        {
+            mouse::Interaction::Pointer
+        } else {
+            interaction
+        }
     }
 
     fn draw(
         &self,
-        _state: &widget::Tree,
-        renderer: &mut Renderer,
-        _theme: &<Renderer as iced::advanced::Renderer>::Theme,
-        _style: &iced::advanced::renderer::Style,
-        layout: iced::advanced::Layout<'_>,
-        _cursor: iced::advanced::mouse::Cursor,
-        _viewport: &iced::Rectangle,
+        tree: &Tree,
+        renderer: &mut iced::Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
     ) {
-        renderer.fill_quad(
-            renderer::Quad {
-                bounds: layout.bounds(),
-                border_radius: 100.0.into(),
-                border_width: 0.0,
-                border_color: Color::TRANSPARENT,
-            },
-            Color::BLACK,
-        );
+        use iced::advanced::Renderer as _;
+
+        let palette = theme.extended_palette();
+        let background = if self.selected {
+            Some(palette.primary.weak.color)
+        } else if cursor.is_over(layout.bounds()) {
+            Some(palette.background.weak.color)
+        } else {
+            None
+        };
+        if let Some(color) = background {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: layout.bounds(),
+                    border_radius: 4.0.into(),
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                },
+                Color { a: 0.5, ..color },
+            );
+        }
+
+        let badge = self.badge_colors(theme);
+        for (index, ((child, state), layout)) in self
+            .children
+            .iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+            .enumerate()
+        {
+            let style = if index == BADGE {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: layout.bounds(),
+                        border_radius: 8.0.into(),
+                        border_width: 0.0,
+                        border_color: Color::TRANSPARENT,
+                    },
+                    badge.color,
+                );
+                renderer::Style {
+                    text_color: badge.text,
+                }
+            } else {
+                *style
+            };
+            child
+                .as_widget()
+                .draw(state, renderer, theme, &style, layout, cursor, viewport);
+        }
     }
-}
 
-impl<'a, Message, Renderer> From<ProtonWidget> for Element<'a, Message, Renderer>
-where
-    Renderer: renderer::Renderer,
-{
-    fn from(proton_widget: ProtonWidget) -> Self {
-        Self::new(proton_widget)
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &iced::Renderer,
+    ) -> Option<overlay::Element<'b, Message, iced::Renderer>> {
+        overlay::from_children(&mut self.children, tree, layout, renderer)
     }
 }
 
-impl ProtonWidget {
-    pub fn new(name: String, installed: bool) -> ProtonWidget {
-        Self { name, installed }
+impl<'a, Message: Clone + 'a> From<ProtonWidget<'a, Message>> for Element<'a, Message> {
+    fn from(proton_widget: ProtonWidget<'a, Message>) -> Self {
+        Self::new(proton_widget)
     }
 }
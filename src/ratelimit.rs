@@ -0,0 +1,136 @@
+use crate::installed::format_size;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Bytes per second, 0 means unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rate(pub u64);
+
+impl Rate {
+    pub const PRESETS: [Rate; 8] = [
+        Rate(0),
+        Rate(256 * 1024),
+        Rate(512 * 1024),
+        Rate(1024 * 1024),
+        Rate(2 * 1024 * 1024),
+        Rate(5 * 1024 * 1024),
+        Rate(10 * 1024 * 1024),
+        Rate(25 * 1024 * 1024),
+    ];
+
+    /// Presets plus `current`, so a rate set from the command line or the
+    /// settings file can still be selected.
+    pub fn choices(current: Rate) -> Vec<Rate> {
+        let mut choices = Rate::PRESETS.to_vec();
+        if !choices.contains(&current) {
+            choices.push(current);
+            choices.sort_by_key(|rate| if rate.0 == 0 { 0 } else { rate.0 + 1 });
+        }
+        choices
+    }
+
+    /// Parses `500K`, `2M`, `1.5M` or a plain number of bytes per second, the
+    /// way curl's `--limit-rate` does.
+    pub fn parse(value: &str) -> Option<Rate> {
+        let value = value.trim();
+        let (number, unit) = match value.char_indices().last()? {
+            (index, c) if c.is_ascii_alphabetic() => (&value[..index], c.to_ascii_lowercase()),
+            _ => (value, 'b'),
+        };
+        let multiplier = match unit {
+            'b' => 1.0,
+            'k' => 1024.0,
+            'm' => 1024.0 * 1024.0,
+            'g' => 1024.0 * 1024.0 * 1024.0,
+            _ => return None,
+        };
+        let number: f64 = number.parse().ok()?;
+        if !number.is_finite() || number < 0.0 {
+            return None;
+        }
+        Some(Rate((number * multiplier) as u64))
+    }
+}
+
+impl std::fmt::Display for Rate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "{}", tr!("rate-unlimited")),
+            rate => write!(f, "{}", tr!("rate-per-second", size = format_size(rate))),
+        }
+    }
+}
+
+/// Spaces out transfers so they average to a rate, using the time the next
+/// byte may be sent rather than a token count.
+#[derive(Debug, Default)]
+pub struct Pacer {
+    next: Option<Instant>,
+}
+
+impl Pacer {
+    pub const fn new() -> Self {
+        Pacer { next: None }
+    }
+
+    /// Books `bytes` at `rate` and returns when the transfer may continue.
+    fn reserve(&mut self, bytes: u64, rate: Rate) -> Option<Instant> {
+        if rate.0 == 0 {
+            self.next = None;
+            return None;
+        }
+        let now = Instant::now();
+        let start = self.next.filter(|next| *next > now).unwrap_or(now);
+        let next = start + Duration::from_secs_f64(bytes as f64 / rate.0 as f64);
+        self.next = Some(next);
+        Some(next)
+    }
+}
+
+static GLOBAL_RATE: AtomicU64 = AtomicU64::new(0);
+/// Shared by all running downloads, so the global cap holds for their sum.
+static GLOBAL_PACER: Mutex<Pacer> = Mutex::new(Pacer::new());
+static JOB_RATES: Mutex<Option<HashMap<String, Rate>>> = Mutex::new(None);
+
+pub fn global() -> Rate {
+    Rate(GLOBAL_RATE.load(Ordering::Relaxed))
+}
+
+pub fn set_global(rate: Rate) {
+    GLOBAL_RATE.store(rate.0, Ordering::Relaxed);
+}
+
+pub fn job(id: &str) -> Rate {
+    let rates = JOB_RATES.lock().unwrap_or_else(|e| e.into_inner());
+    rates
+        .as_ref()
+        .and_then(|rates| rates.get(id).copied())
+        .unwrap_or(Rate(0))
+}
+
+pub fn set_job(id: &str, rate: Rate) {
+    let mut rates = JOB_RATES.lock().unwrap_or_else(|e| e.into_inner());
+    let rates = rates.get_or_insert_with(HashMap::new);
+    if rate.0 == 0 {
+        rates.remove(id);
+    } else {
+        rates.insert(id.to_string(), rate);
+    }
+}
+
+/// Waits as long as the global and the per job limit require after `bytes`
+/// were transferred. Limits are read on every call, so changes apply to
+/// downloads that are already running.
+pub async fn throttle(id: &str, pacer: &mut Pacer, bytes: u64) {
+    let global = GLOBAL_PACER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .reserve(bytes, global());
+    let own = pacer.reserve(bytes, job(id));
+
+    if let Some(until) = global.max(own) {
+        tokio::time::sleep_until(until.into()).await;
+    }
+}
@@ -0,0 +1,334 @@
+use crate::paths;
+use crate::signature::TrustedKey;
+use crate::source::Channel;
+use crate::target::{Destination, TargetKind};
+use crate::DownloaderError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const CURRENT_VERSION: i64 = 2;
+const FILE_NAME: &str = "settings.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeChoice {
+    Dark,
+    Light,
+}
+
+impl ThemeChoice {
+    pub const ALL: [ThemeChoice; 2] = [ThemeChoice::Dark, ThemeChoice::Light];
+}
+
+impl std::fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemeChoice::Dark => write!(f, "{}", tr!("theme-dark")),
+            ThemeChoice::Light => write!(f, "{}", tr!("theme-light")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReleaseFilter {
+    All,
+    Installed,
+    NotInstalled,
+    InUse,
+    Prerelease,
+}
+
+impl ReleaseFilter {
+    pub const ALL: [ReleaseFilter; 5] = [
+        ReleaseFilter::All,
+        ReleaseFilter::Installed,
+        ReleaseFilter::NotInstalled,
+        ReleaseFilter::InUse,
+        ReleaseFilter::Prerelease,
+    ];
+}
+
+impl std::fmt::Display for ReleaseFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseFilter::All => write!(f, "{}", tr!("filter-all")),
+            ReleaseFilter::Installed => write!(f, "{}", tr!("filter-installed")),
+            ReleaseFilter::NotInstalled => write!(f, "{}", tr!("filter-not-installed")),
+            ReleaseFilter::InUse => write!(f, "{}", tr!("filter-in-use")),
+            ReleaseFilter::Prerelease => write!(f, "{}", tr!("filter-prerelease")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseSort {
+    Version,
+    Published,
+    Size,
+}
+
+impl ReleaseSort {
+    pub const ALL: [ReleaseSort; 3] = [
+        ReleaseSort::Version,
+        ReleaseSort::Published,
+        ReleaseSort::Size,
+    ];
+}
+
+impl std::fmt::Display for ReleaseSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseSort::Version => write!(f, "{}", tr!("sort-version")),
+            ReleaseSort::Published => write!(f, "{}", tr!("sort-published")),
+            ReleaseSort::Size => write!(f, "{}", tr!("sort-size")),
+        }
+    }
+}
+
+/// What the background check does with an update it finds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdatePolicy {
+    Notify,
+    Install,
+}
+
+impl UpdatePolicy {
+    pub const ALL: [UpdatePolicy; 2] = [UpdatePolicy::Notify, UpdatePolicy::Install];
+}
+
+impl std::fmt::Display for UpdatePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdatePolicy::Notify => write!(f, "{}", tr!("policy-notify")),
+            UpdatePolicy::Install => write!(f, "{}", tr!("policy-install")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub version: i64,
+    pub destinations: Vec<Destination>,
+    /// Name of the destination selected when the application starts.
+    pub default_destination: Option<String>,
+    pub theme: ThemeChoice,
+    /// Ids of the enabled entries of [`crate::source::SOURCES`].
+    pub sources: Vec<String>,
+    /// Channel each source is followed on for updates, stable when missing.
+    pub channels: BTreeMap<String, Channel>,
+    /// Lists pre-release and nightly builds next to the stable ones.
+    pub show_unstable: bool,
+    /// Text the release list was last searched for.
+    pub release_search: String,
+    pub release_filter: ReleaseFilter,
+    pub release_sort: ReleaseSort,
+    pub parallel_downloads: u32,
+    /// Number of installed builds kept per source, 0 keeps everything.
+    pub retention: u32,
+    /// `http://`, `https://` or `socks5://` URL, the environment is used
+    /// when unset.
+    pub proxy: Option<String>,
+    pub proxy_user: Option<String>,
+    pub proxy_password: Option<String>,
+    /// Hosts, domains and networks reached without the proxy.
+    pub no_proxy: Vec<String>,
+    /// Download cap for all jobs together in bytes per second, 0 is unlimited.
+    pub bandwidth_limit: u64,
+    /// Size limit of the tarball cache in MiB, 0 disables caching.
+    pub cache_limit_mb: u64,
+    /// Keys that sign releases, sources without any are not checked.
+    pub signing_keys: Vec<TrustedKey>,
+    pub update_policy: UpdatePolicy,
+    /// Minimum time between two background checks.
+    pub check_interval_hours: u32,
+    /// Id of the interface language, the environment decides when unset.
+    pub language: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            destinations: Vec::new(),
+            default_destination: None,
+            theme: ThemeChoice::Dark,
+            sources: vec![String::from("ge-proton")],
+            channels: BTreeMap::new(),
+            show_unstable: false,
+            release_search: String::new(),
+            release_filter: ReleaseFilter::All,
+            release_sort: ReleaseSort::Version,
+            parallel_downloads: 1,
+            retention: 0,
+            proxy: None,
+            proxy_user: None,
+            proxy_password: None,
+            no_proxy: Vec::new(),
+            bandwidth_limit: 0,
+            cache_limit_mb: 4096,
+            signing_keys: Vec::new(),
+            update_policy: UpdatePolicy::Notify,
+            check_interval_hours: 24,
+            language: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn destination(&self, name: &str) -> Option<&Destination> {
+        self.destinations
+            .iter()
+            .find(|destination| destination.name == name)
+    }
+
+    pub fn default_destination(&self) -> Option<&Destination> {
+        self.default_destination
+            .as_deref()
+            .and_then(|name| self.destination(name))
+            .or(self.destinations.first())
+    }
+
+    pub fn cache_limit(&self) -> u64 {
+        self.cache_limit_mb * 1024 * 1024
+    }
+
+    pub fn keys_for(&self, source: &str) -> Vec<TrustedKey> {
+        self.signing_keys
+            .iter()
+            .filter(|key| key.source == source)
+            .cloned()
+            .collect()
+    }
+
+    pub fn channel(&self, source: &str) -> Channel {
+        self.channels
+            .get(source)
+            .copied()
+            .unwrap_or(Channel::Stable)
+    }
+
+    pub fn source_enabled(&self, id: &str) -> bool {
+        self.sources.iter().any(|source| source == id)
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    Some(paths::config_dir()?.join(FILE_NAME))
+}
+
+/// Brings a settings table written by an older release up to
+/// [`CURRENT_VERSION`], one version step at a time.
+fn migrate(mut table: toml::Table) -> Result<toml::Table, DownloaderError> {
+    let mut version = table
+        .get("version")
+        .and_then(|version| version.as_integer())
+        .unwrap_or(0);
+
+    if version > CURRENT_VERSION {
+        return Err(DownloaderError::SettingsError(tr!(
+            "error-settings-too-new",
+            version = version
+        )));
+    }
+
+    while version < CURRENT_VERSION {
+        match version {
+            // Files from before versioning only lack the version field.
+            0 => {}
+            1 => migrate_v1(&mut table),
+            version => {
+                return Err(DownloaderError::SettingsError(tr!(
+                    "error-settings-migration",
+                    version = version
+                )))
+            }
+        }
+        version += 1;
+    }
+
+    table.insert(String::from("version"), toml::Value::Integer(version));
+    Ok(table)
+}
+
+/// Version 1 had a single Steam path override and a launcher kind, version 2
+/// replaces both with named destinations.
+fn migrate_v1(table: &mut toml::Table) {
+    let kind = table
+        .remove("target")
+        .and_then(|kind| kind.try_into::<TargetKind>().ok())
+        .unwrap_or(TargetKind::Steam);
+    let mut destinations = Vec::new();
+
+    if let Some(path) = table
+        .remove("install_target")
+        .and_then(|path| path.as_str().map(PathBuf::from))
+    {
+        destinations.push(Destination {
+            name: String::from("Steam (custom)"),
+            kind: TargetKind::Steam,
+            path,
+        });
+    }
+
+    let default = match destinations.first() {
+        Some(destination) if kind == TargetKind::Steam => destination.name.clone(),
+        _ => kind.to_string(),
+    };
+    table.insert(
+        String::from("default_destination"),
+        toml::Value::String(default),
+    );
+
+    if let Ok(toml::Value::Array(destinations)) = toml::Value::try_from(destinations) {
+        table.insert(
+            String::from("destinations"),
+            toml::Value::Array(destinations),
+        );
+    }
+}
+
+pub fn load() -> Result<Config, DownloaderError> {
+    let path = match settings_path() {
+        Some(path) if path.exists() => path,
+        _ => return Ok(Config::default()),
+    };
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| DownloaderError::SettingsError(e.to_string()))?;
+    let table: toml::Table =
+        toml::from_str(&content).map_err(|e| DownloaderError::SettingsError(e.to_string()))?;
+
+    toml::Value::Table(migrate(table)?)
+        .try_into()
+        .map_err(|e| DownloaderError::SettingsError(e.to_string()))
+}
+
+pub async fn save(config: Config) -> Result<(), DownloaderError> {
+    let path = settings_path()
+        .ok_or_else(|| DownloaderError::SettingsError(tr!("error-no-config-dir")))?;
+    let dir = path
+        .parent()
+        .ok_or_else(|| DownloaderError::SettingsError(tr!("error-no-config-dir")))?;
+    fs::create_dir_all(dir).map_err(|e| DownloaderError::SettingsError(e.to_string()))?;
+
+    let content = toml::to_string_pretty(&config)
+        .map_err(|e| DownloaderError::SettingsError(e.to_string()))?;
+    // The file holds the proxy password, tempfile creates it readable by the
+    // owner only. Each save gets its own file, so saves can't mix.
+    let mut file = tempfile::Builder::new()
+        .prefix(FILE_NAME)
+        .tempfile_in(dir)
+        .map_err(|e| DownloaderError::SettingsError(e.to_string()))?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| DownloaderError::SettingsError(e.to_string()))?;
+    file.persist(&path)
+        .map_err(|e| DownloaderError::SettingsError(e.to_string()))?;
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use crate::DownloaderError;
+use minisign_verify::{PublicKey, Signature};
+use pgp::composed::{Deserializable, SignedPublicKey, StandaloneSignature};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureKind {
+    Minisign,
+    OpenPgp,
+}
+
+impl SignatureKind {
+    /// Detached signatures are published as `<asset>.minisig`, or
+    /// `<asset>.sig` / `<asset>.asc` for OpenPGP.
+    pub fn from_asset(name: &str) -> Option<SignatureKind> {
+        if name.ends_with(".minisig") {
+            Some(SignatureKind::Minisign)
+        } else if name.ends_with(".sig") || name.ends_with(".asc") {
+            Some(SignatureKind::OpenPgp)
+        } else {
+            None
+        }
+    }
+}
+
+/// A key trusted to sign the releases of one source. Once a source has keys,
+/// its releases must carry a valid signature from one of them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedKey {
+    /// Id of the entry in [`crate::source::SOURCES`].
+    pub source: String,
+    /// Shown in the "signed by" badge.
+    pub name: String,
+    pub kind: SignatureKind,
+    /// Minisign public key, either the base64 line or the whole `.pub` file,
+    /// or an ASCII armored OpenPGP public key.
+    pub key: String,
+}
+
+/// Checks the detached `signature` of the file at `path` against the keys of
+/// the matching kind and returns the name of the key that made it.
+pub fn verify(
+    path: &Path,
+    kind: SignatureKind,
+    signature: &[u8],
+    keys: &[TrustedKey],
+) -> Result<String, DownloaderError> {
+    keys.iter()
+        .filter(|key| key.kind == kind)
+        .find(|key| match kind {
+            SignatureKind::Minisign => verify_minisign(path, signature, &key.key),
+            SignatureKind::OpenPgp => verify_openpgp(path, signature, &key.key),
+        })
+        .map(|key| key.name.clone())
+        .ok_or(DownloaderError::SignatureInvalid)
+}
+
+fn verify_minisign(path: &Path, signature: &[u8], key: &str) -> bool {
+    let key = key.trim();
+    let public_key = if key.lines().count() > 1 {
+        PublicKey::decode(key)
+    } else {
+        PublicKey::from_base64(key)
+    };
+    let public_key = match public_key {
+        Ok(public_key) => public_key,
+        Err(_) => return false,
+    };
+    let signature = match std::str::from_utf8(signature).map(Signature::decode) {
+        Ok(Ok(signature)) => signature,
+        _ => return false,
+    };
+
+    let mut verifier = match public_key.verify_stream(&signature) {
+        Ok(verifier) => verifier,
+        Err(_) => return false,
+    };
+    let mut file = match File::open(path) {
+        Ok(file) => BufReader::new(file),
+        Err(_) => return false,
+    };
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => verifier.update(&buffer[..read]),
+            Err(_) => return false,
+        }
+    }
+    verifier.finalize().is_ok()
+}
+
+fn verify_openpgp(path: &Path, signature: &[u8], key: &str) -> bool {
+    let key = match SignedPublicKey::from_string(key) {
+        Ok((key, _)) => key,
+        Err(_) => return false,
+    };
+    let signature = match StandaloneSignature::from_armor_single(Cursor::new(signature)) {
+        Ok((signature, _)) => signature,
+        Err(_) => match StandaloneSignature::from_bytes(signature) {
+            Ok(signature) => signature,
+            Err(_) => return false,
+        },
+    };
+
+    let open = || File::open(path).map(BufReader::new);
+    let by_primary = open()
+        .map(|file| signature.signature.verify(&key, file).is_ok())
+        .unwrap_or(false);
+    by_primary
+        || key.public_subkeys.iter().any(|subkey| {
+            open()
+                .map(|file| signature.signature.verify(subkey, file).is_ok())
+                .unwrap_or(false)
+        })
+}
@@ -0,0 +1,180 @@
+use octocrab::models::repos::Release;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToolKind {
+    Proton,
+    Wine,
+}
+
+/// Ordered from most to least conservative, a channel includes the builds
+/// of every channel before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    Stable,
+    Prerelease,
+    Nightly,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 3] = [Channel::Stable, Channel::Prerelease, Channel::Nightly];
+
+    pub fn includes(&self, other: Channel) -> bool {
+        other <= *self
+    }
+}
+
+impl std::fmt::Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Channel::Stable => write!(f, "{}", tr!("channel-stable")),
+            Channel::Prerelease => write!(f, "{}", tr!("channel-prerelease")),
+            Channel::Nightly => write!(f, "{}", tr!("channel-nightly")),
+        }
+    }
+}
+
+/// A regex of a source definition, compiled on first use.
+pub struct Pattern {
+    text: &'static str,
+    compiled: OnceLock<Regex>,
+}
+
+impl Pattern {
+    const fn new(text: &'static str) -> Pattern {
+        Pattern {
+            text,
+            compiled: OnceLock::new(),
+        }
+    }
+
+    pub fn is_match(&self, haystack: &str) -> bool {
+        self.compiled
+            .get_or_init(|| Regex::new(self.text).expect("invalid source pattern"))
+            .is_match(haystack)
+    }
+}
+
+impl std::fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.text)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl Eq for Pattern {}
+
+impl std::hash::Hash for Pattern {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.text.hash(state)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Source {
+    pub id: &'static str,
+    pub kind: ToolKind,
+    pub name: &'static str,
+    pub owner: &'static str,
+    pub repo: &'static str,
+    /// Matches the folder names of installed builds from this source.
+    pub name_pattern: Pattern,
+    /// Matches tags of nightly-like builds, which are published as regular
+    /// releases or pre-releases.
+    pub nightly_pattern: Option<Pattern>,
+}
+
+/// A static rather than a const, so each pattern is compiled only once.
+pub static SOURCES: [Source; 2] = [
+    Source {
+        id: "ge-proton",
+        kind: ToolKind::Proton,
+        name: "GE-Proton",
+        owner: "GloriousEggroll",
+        repo: "proton-ge-custom",
+        name_pattern: Pattern::new(r"^(GE-Proton|Proton-.*-GE)"),
+        // Release candidates such as `GE-Proton8-26-rc2` and dated builds.
+        nightly_pattern: Some(Pattern::new(r"-rc\d*$|-\d{8}$")),
+    },
+    Source {
+        id: "wine-ge",
+        kind: ToolKind::Wine,
+        name: "Wine-GE",
+        owner: "GloriousEggroll",
+        repo: "wine-ge-custom",
+        name_pattern: Pattern::new(r"^(wine-)?lutris-(GE|ge)-"),
+        // Builds of Wine release candidates such as `7.0rc3-GE-1`, release
+        // candidates of a build and dated builds.
+        nightly_pattern: Some(Pattern::new(r"rc\d*-GE-|-rc\d*$|-\d{8}$")),
+    },
+];
+
+pub fn find(id: &str) -> Option<&'static Source> {
+    SOURCES.iter().find(|source| source.id == id)
+}
+
+impl Source {
+    pub fn owns(&self, name: &str) -> bool {
+        self.name_pattern.is_match(name)
+    }
+
+    /// Channel a release is published in, drafts are in none.
+    pub fn channel(&self, release: &Release) -> Option<Channel> {
+        if release.draft {
+            return None;
+        }
+        let nightly = self
+            .nightly_pattern
+            .as_ref()
+            .is_some_and(|pattern| pattern.is_match(&release.tag_name));
+        if nightly {
+            Some(Channel::Nightly)
+        } else if release.prerelease {
+            Some(Channel::Prerelease)
+        } else {
+            Some(Channel::Stable)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nightly(id: &str, tag: &str) -> bool {
+        find(id)
+            .and_then(|source| source.nightly_pattern.as_ref())
+            .is_some_and(|pattern| pattern.is_match(tag))
+    }
+
+    #[test]
+    fn owned_names() {
+        let ge_proton = find("ge-proton").unwrap();
+        let wine_ge = find("wine-ge").unwrap();
+        assert!(ge_proton.owns("GE-Proton9-4"));
+        assert!(ge_proton.owns("Proton-6.21-GE-2"));
+        assert!(!ge_proton.owns("lutris-GE-Proton8-26-x86_64"));
+        assert!(wine_ge.owns("lutris-GE-Proton8-26-x86_64"));
+        assert!(wine_ge.owns("wine-lutris-ge-7.0-1"));
+    }
+
+    #[test]
+    fn nightly_tags() {
+        assert!(nightly("ge-proton", "GE-Proton8-26-rc2"));
+        assert!(nightly("ge-proton", "GE-Proton9-4-20240301"));
+        assert!(!nightly("ge-proton", "GE-Proton9-4"));
+        assert!(!nightly("ge-proton", "GE-Proton8-26-LoL"));
+        assert!(nightly("wine-ge", "7.0rc3-GE-1"));
+        assert!(nightly("wine-ge", "GE-Proton8-26-rc1"));
+        assert!(!nightly("wine-ge", "GE-Proton8-26"));
+        assert!(!nightly("wine-ge", "7.0-GE-1"));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::vdf::{self, Section};
+use crate::DownloaderError;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const FLATPAK_ID: &str = "com.valvesoftware.Steam";
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Native and Flatpak installs can live side by side, each with its own
+/// `compatibilitytools.d`.
+pub fn steam_roots() -> Vec<(String, PathBuf)> {
+    let home = match home::home_dir() {
+        Some(home) => home,
+        None => return Vec::new(),
+    };
+    let mut roots = Vec::new();
+
+    if let Some(native) = [".steam/steam", ".local/share/Steam"]
+        .iter()
+        .map(|path| home.join(path))
+        .find(|path| path.is_dir())
+    {
+        roots.push((String::from("Steam"), native));
+    }
+
+    let flatpak = home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam");
+    if flatpak.is_dir() {
+        roots.push((String::from("Steam (Flatpak)"), flatpak));
+    }
+
+    roots
+}
+
+pub fn steam_root() -> Option<PathBuf> {
+    let home = home::home_dir()?;
+    [
+        ".steam/steam",
+        ".local/share/Steam",
+        ".var/app/com.valvesoftware.Steam/.local/share/Steam",
+    ]
+    .iter()
+    .map(|path| home.join(path))
+    .find(|path| path.is_dir())
+}
+
+pub fn compat_tools_dir() -> Option<PathBuf> {
+    Some(steam_root()?.join("compatibilitytools.d"))
+}
+
+pub fn config_path(root: &Path) -> PathBuf {
+    root.join("config/config.vdf")
+}
+
+/// Steam install whose `compatibilitytools.d` is `compat_dir`, so that
+/// config edits go to the client that actually loads tools from there.
+pub fn root_for(compat_dir: &Path) -> Option<PathBuf> {
+    let root = compat_dir.parent()?;
+    config_path(root).is_file().then(|| root.to_path_buf())
+}
+
+fn pid_file() -> Option<PathBuf> {
+    Some(home::home_dir()?.join(".steam/steam.pid"))
+}
+
+fn parent_pid(pid: u32) -> Option<u32> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let line = status.lines().find_map(|line| line.strip_prefix("PPid:"))?;
+    line.trim().parse().ok()
+}
+
+/// Our own `steam -shutdown` call is named steam as well, so children of this
+/// process are never taken for the client.
+fn is_steam_process(pid: u32) -> bool {
+    let is_steam = match fs::read_to_string(format!("/proc/{}/comm", pid)) {
+        Ok(comm) => comm.trim() == "steam",
+        Err(_) => false,
+    };
+    is_steam && parent_pid(pid) != Some(std::process::id())
+}
+
+fn pid_from_file() -> Option<u32> {
+    let content = fs::read_to_string(pid_file()?).ok()?;
+    content.trim().parse().ok()
+}
+
+fn scan_proc() -> bool {
+    let entries = match fs::read_dir("/proc") {
+        Ok(entries) => entries,
+        Err(_) => return false,
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .any(is_steam_process)
+}
+
+/// The pid file is left behind when Steam crashes, so a pid is only trusted
+/// when the process behind it is actually the Steam client.
+pub fn is_running() -> bool {
+    if let Some(pid) = pid_from_file() {
+        if is_steam_process(pid) {
+            return true;
+        }
+    }
+    scan_proc()
+}
+
+fn is_flatpak(root: &Path) -> bool {
+    root.to_string_lossy().contains(FLATPAK_ID)
+}
+
+/// Asks the running client to exit the same way `steam -shutdown` does, which
+/// lets Steam flush its own config before we touch it. The call only hands
+/// the request over, it is waited for so it does not linger as a zombie.
+pub async fn request_shutdown(root: &Path) -> Result<(), DownloaderError> {
+    let mut command = if is_flatpak(root) {
+        let mut command = Command::new("flatpak");
+        command.args(["run", FLATPAK_ID, "-shutdown"]);
+        command
+    } else {
+        let mut command = Command::new("steam");
+        command.arg("-shutdown");
+        command
+    };
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .or(Err(DownloaderError::SteamShutdownFailed))?;
+
+    let wait = tokio::task::spawn_blocking(move || child.wait());
+    match tokio::time::timeout(SHUTDOWN_TIMEOUT, wait).await {
+        Ok(Ok(Ok(_))) => Ok(()),
+        _ => Err(DownloaderError::SteamShutdownFailed),
+    }
+}
+
+pub async fn wait_for_exit(timeout: Duration) -> Result<(), DownloaderError> {
+    let mut waited = Duration::ZERO;
+    while is_running() {
+        if waited >= timeout {
+            return Err(DownloaderError::SteamShutdownFailed);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+        waited += POLL_INTERVAL;
+    }
+    Ok(())
+}
+
+pub async fn shutdown_and_wait(root: PathBuf) -> Result<(), DownloaderError> {
+    if !is_running() {
+        return Ok(());
+    }
+    request_shutdown(&root).await?;
+    wait_for_exit(SHUTDOWN_TIMEOUT).await
+}
+
+/// Every change to a Steam config file has to go through here: Steam
+/// rewrites its config on exit, so edits made while it runs would be lost.
+pub fn modify_config<F>(path: &Path, modify: F) -> Result<(), DownloaderError>
+where
+    F: FnOnce(&mut Section),
+{
+    if is_running() {
+        return Err(DownloaderError::SteamRunning);
+    }
+
+    let mut config = vdf::read_text_file(path).or(Err(DownloaderError::SteamConfigError))?;
+    modify(&mut config);
+    vdf::write_text_file(path, &config).or(Err(DownloaderError::SteamConfigError))
+}
+
+/// Names of the compatibility tools referenced in the `CompatToolMapping`
+/// of the Steam install at `root`, either as the global default or for a
+/// single game.
+pub fn tools_in_use(root: &Path) -> HashSet<String> {
+    let config = match vdf::read_text_file(&config_path(root)) {
+        Ok(config) => config,
+        Err(_) => return HashSet::new(),
+    };
+
+    let mapping = match config.path(&[
+        "InstallConfigStore",
+        "Software",
+        "Valve",
+        "Steam",
+        "CompatToolMapping",
+    ]) {
+        Some(mapping) => mapping,
+        None => return HashSet::new(),
+    };
+
+    mapping
+        .entries()
+        .filter_map(|entry| entry.value.as_section()?.get_str("name"))
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Tools used by any of the Steam installs, for decisions that delete data.
+pub fn tools_in_use_by_any() -> HashSet<String> {
+    steam_roots()
+        .iter()
+        .flat_map(|(_, root)| tools_in_use(root))
+        .collect()
+}
+
+/// Makes `name` the compatibility tool used for all titles without a
+/// per-game override (app id 0 in `CompatToolMapping`) of the Steam install
+/// at `root`.
+pub async fn set_default_compat_tool(root: PathBuf, name: String) -> Result<(), DownloaderError> {
+    modify_config(&config_path(&root), |config| {
+        let mapping = config.path_entry(&[
+            "InstallConfigStore",
+            "Software",
+            "Valve",
+            "Steam",
+            "CompatToolMapping",
+        ]);
+        let default = mapping.section_entry("0");
+        default.set("name", name);
+        default.set("config", "");
+        default.set("priority", "75");
+    })
+}
@@ -0,0 +1,192 @@
+use crate::installed::{self, InstalledTool};
+use crate::source::ToolKind;
+use crate::steam;
+use crate::DownloaderError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Launchers we know how to install compatibility tools for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TargetKind {
+    Steam,
+    Lutris,
+    HeroicWine,
+    HeroicProton,
+    Bottles,
+    BottlesFlatpak,
+}
+
+impl TargetKind {
+    pub const ALL: [TargetKind; 6] = [
+        TargetKind::Steam,
+        TargetKind::Lutris,
+        TargetKind::HeroicWine,
+        TargetKind::HeroicProton,
+        TargetKind::Bottles,
+        TargetKind::BottlesFlatpak,
+    ];
+
+    pub fn default_path(&self) -> Option<PathBuf> {
+        let home = home::home_dir()?;
+        match self {
+            TargetKind::Steam => steam::compat_tools_dir(),
+            TargetKind::Lutris => Some(home.join(".local/share/lutris/runners/wine")),
+            TargetKind::HeroicWine => Some(home.join(".config/heroic/tools/wine")),
+            TargetKind::HeroicProton => Some(home.join(".config/heroic/tools/proton")),
+            TargetKind::Bottles => Some(home.join(".local/share/bottles/runners")),
+            TargetKind::BottlesFlatpak => {
+                Some(home.join(".var/app/com.usebottles.bottles/data/bottles/runners"))
+            }
+        }
+    }
+
+    /// Directory that only exists once the launcher has been set up.
+    fn launcher_dir(&self) -> Option<PathBuf> {
+        let home = home::home_dir()?;
+        match self {
+            TargetKind::Steam => steam::steam_root(),
+            TargetKind::Lutris => Some(home.join(".local/share/lutris")),
+            TargetKind::HeroicWine | TargetKind::HeroicProton => Some(home.join(".config/heroic")),
+            TargetKind::Bottles => Some(home.join(".local/share/bottles")),
+            TargetKind::BottlesFlatpak => Some(home.join(".var/app/com.usebottles.bottles")),
+        }
+    }
+
+    pub fn accepts(&self, kind: ToolKind) -> bool {
+        match self {
+            TargetKind::Steam | TargetKind::HeroicProton => kind == ToolKind::Proton,
+            TargetKind::Lutris | TargetKind::HeroicWine => kind == ToolKind::Wine,
+            TargetKind::Bottles | TargetKind::BottlesFlatpak => true,
+        }
+    }
+
+    /// Whether the launcher would actually pick up the build in `path`.
+    /// Steam needs the `compatibilitytool.vdf`, the others look for the
+    /// wine binary or the proton launcher script.
+    pub fn is_complete(&self, path: &Path) -> bool {
+        match self {
+            TargetKind::Steam => path.join("compatibilitytool.vdf").is_file(),
+            TargetKind::Lutris | TargetKind::HeroicWine => path.join("bin/wine").exists(),
+            TargetKind::HeroicProton => path.join("proton").is_file(),
+            TargetKind::Bottles | TargetKind::BottlesFlatpak => {
+                path.join("bin/wine").exists() || path.join("proton").is_file()
+            }
+        }
+    }
+
+    /// Inspects every build in the launcher's runner directory, the way
+    /// `get_installed` does for Steam's `compatibilitytools.d`.
+    pub fn scan(&self, dir: &Path) -> io::Result<Vec<InstalledTool>> {
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut retval = Vec::new();
+        for entry in fs::read_dir(dir)?.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            if path.is_dir() {
+                tracing::debug!("found folder {}", path.display());
+                let mut tool = installed::inspect(&path);
+                tool.valid = tool.size.is_some() && self.is_complete(&path);
+                retval.push(tool);
+            }
+        }
+        Ok(retval)
+    }
+}
+
+impl std::fmt::Display for TargetKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TargetKind::Steam => "Steam",
+            TargetKind::Lutris => "Lutris",
+            TargetKind::HeroicWine => "Heroic (Wine)",
+            TargetKind::HeroicProton => "Heroic (Proton)",
+            TargetKind::Bottles => "Bottles",
+            TargetKind::BottlesFlatpak => "Bottles (Flatpak)",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A named place to install tools to, e.g. the Flatpak Steam on a second drive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Destination {
+    pub name: String,
+    pub kind: TargetKind,
+    pub path: PathBuf,
+}
+
+impl std::fmt::Display for Destination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Destinations for every launcher found in the home directory that we can
+/// write to.
+pub fn detect() -> Vec<Destination> {
+    let mut found: Vec<Destination> = steam::steam_roots()
+        .into_iter()
+        .map(|(name, root)| Destination {
+            name,
+            kind: TargetKind::Steam,
+            path: root.join("compatibilitytools.d"),
+        })
+        .collect();
+
+    for kind in TargetKind::ALL
+        .iter()
+        .filter(|kind| **kind != TargetKind::Steam)
+    {
+        let installed = kind.launcher_dir().map(|dir| dir.is_dir()).unwrap_or(false);
+        if let (true, Some(path)) = (installed, kind.default_path()) {
+            found.push(Destination {
+                name: kind.to_string(),
+                kind: *kind,
+                path,
+            });
+        }
+    }
+
+    found.retain(|destination| match check_writable(&destination.path) {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!("skipping {}: {}", destination.name, e);
+            false
+        }
+    });
+    found
+}
+
+/// Creates the directory if needed and proves it is writable by creating and
+/// removing a scratch file in it.
+pub fn check_writable(path: &Path) -> Result<(), DownloaderError> {
+    let not_writable = || DownloaderError::NotWritable(path.display().to_string());
+
+    fs::create_dir_all(path).map_err(|_| not_writable())?;
+    tempfile::Builder::new()
+        .prefix(".proton_downloader")
+        .tempfile_in(path)
+        .map(|_| ())
+        .map_err(|_| not_writable())
+}
+
+pub async fn validate(destination: Destination) -> Result<Destination, DownloaderError> {
+    if destination.name.trim().is_empty() {
+        return Err(DownloaderError::SettingsError(tr!(
+            "error-destination-name"
+        )));
+    }
+    // An empty path would probe the working directory instead.
+    if !destination.path.is_absolute() {
+        return Err(DownloaderError::SettingsError(tr!(
+            "error-destination-path"
+        )));
+    }
+    check_writable(&destination.path)?;
+    Ok(destination)
+}
@@ -0,0 +1,802 @@
+//! Reader and writer for Valve's KeyValues (VDF) formats.
+//!
+//! Text VDF (`config.vdf`, `libraryfolders.vdf`, `compatibilitytool.vdf`,
+//! `appmanifest_*.acf`) is parsed into a [`Section`] that remembers key order,
+//! comments and blank lines so it can be written back without reshuffling the
+//! file. Binary VDF (`shortcuts.vdf`) is parsed into the same tree.
+
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Section {
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Entry(Entry),
+    /// Comment text without the leading `//`.
+    Comment(String),
+    Blank,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub key: String,
+    pub value: Value,
+    /// Platform conditional such as `[$WIN32]`, stored without the brackets.
+    pub condition: Option<String>,
+    /// Comment on the same line as the end of the entry.
+    pub comment: Option<String>,
+    /// Comment on the same line as the key when the value starts on a later
+    /// line, as before the `{` of a section.
+    pub key_comment: Option<String>,
+    /// Comments and blank lines between the key line and the value.
+    pub before_value: Vec<Item>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Section(Section),
+    Int32(i32),
+    Float32(f32),
+    Pointer(i32),
+    WideString(String),
+    Color(i32),
+    UInt64(u64),
+    Int64(i64),
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum VdfError {
+    #[error("failed to access vdf file: {0}")]
+    Io(String),
+    #[error("syntax error on line {line}: {message}")]
+    Syntax { line: usize, message: String },
+    #[error("unexpected end of file")]
+    UnexpectedEof,
+    #[error("invalid binary vdf at offset {offset}: {message}")]
+    Binary { offset: usize, message: String },
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) | Value::WideString(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_section(&self) -> Option<&Section> {
+        match self {
+            Value::Section(section) => Some(section),
+            _ => None,
+        }
+    }
+
+    pub fn as_section_mut(&mut self) -> Option<&mut Section> {
+        match self {
+            Value::Section(section) => Some(section),
+            _ => None,
+        }
+    }
+
+    /// Integer view of the value, also accepting numeric strings as text VDF
+    /// stores every number as a string.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Int32(v) | Value::Pointer(v) | Value::Color(v) => Some(*v as i64),
+            Value::Int64(v) => Some(*v),
+            Value::UInt64(v) => i64::try_from(*v).ok(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn to_text(&self) -> String {
+        match self {
+            Value::String(s) | Value::WideString(s) => s.clone(),
+            Value::Int32(v) | Value::Pointer(v) | Value::Color(v) => v.to_string(),
+            Value::Float32(v) => v.to_string(),
+            Value::UInt64(v) => v.to_string(),
+            Value::Int64(v) => v.to_string(),
+            Value::Section(_) => String::new(),
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<Section> for Value {
+    fn from(value: Section) -> Self {
+        Value::Section(value)
+    }
+}
+
+impl Section {
+    pub fn new() -> Section {
+        Self { items: Vec::new() }
+    }
+
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Entry(entry) => Some(entry),
+            _ => None,
+        })
+    }
+
+    pub fn entries_mut(&mut self) -> impl Iterator<Item = &mut Entry> {
+        self.items.iter_mut().filter_map(|item| match item {
+            Item::Entry(entry) => Some(entry),
+            _ => None,
+        })
+    }
+
+    /// Looks up a key the way Steam does, ignoring ASCII case.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries()
+            .find(|entry| entry.key.eq_ignore_ascii_case(key))
+            .map(|entry| &entry.value)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.entries_mut()
+            .find(|entry| entry.key.eq_ignore_ascii_case(key))
+            .map(|entry| &mut entry.value)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    pub fn get_section(&self, key: &str) -> Option<&Section> {
+        self.get(key).and_then(Value::as_section)
+    }
+
+    pub fn get_section_mut(&mut self, key: &str) -> Option<&mut Section> {
+        self.get_mut(key).and_then(Value::as_section_mut)
+    }
+
+    /// Follows a chain of nested sections, e.g. `["InstallConfigStore", "Software"]`.
+    pub fn path(&self, keys: &[&str]) -> Option<&Section> {
+        let mut section = self;
+        for key in keys {
+            section = section.get_section(key)?;
+        }
+        Some(section)
+    }
+
+    /// Returns the named subsection, appending an empty one if it does not
+    /// exist yet. A non-section value under the same key is replaced.
+    pub fn section_entry(&mut self, key: &str) -> &mut Section {
+        if !matches!(self.get(key), Some(Value::Section(_))) {
+            self.set(key, Section::new());
+        }
+        self.get_section_mut(key).unwrap()
+    }
+
+    /// Like [`Section::section_entry`] but for a whole chain of keys.
+    pub fn path_entry(&mut self, keys: &[&str]) -> &mut Section {
+        let mut section = self;
+        for key in keys {
+            section = section.section_entry(key);
+        }
+        section
+    }
+
+    /// Replaces the value of an existing key in place, keeping its position
+    /// and comment, or appends a new entry.
+    pub fn set(&mut self, key: &str, value: impl Into<Value>) {
+        let value = value.into();
+        if let Some(existing) = self.get_mut(key) {
+            *existing = value;
+        } else {
+            self.push(key, value);
+        }
+    }
+
+    pub fn push(&mut self, key: &str, value: impl Into<Value>) {
+        self.items.push(Item::Entry(Entry {
+            key: key.to_string(),
+            value: value.into(),
+            condition: None,
+            comment: None,
+            key_comment: None,
+            before_value: Vec::new(),
+        }));
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let index = self.items.iter().position(|item| match item {
+            Item::Entry(entry) => entry.key.eq_ignore_ascii_case(key),
+            _ => false,
+        })?;
+        match self.items.remove(index) {
+            Item::Entry(entry) => Some(entry.value),
+            _ => None,
+        }
+    }
+}
+
+pub fn read_text_file(path: &Path) -> Result<Section, VdfError> {
+    let content = fs::read_to_string(path).map_err(|e| VdfError::Io(e.to_string()))?;
+    parse_text(&content)
+}
+
+/// Writes the file through a temporary sibling so a crash never leaves a
+/// truncated Steam config behind.
+pub fn write_text_file(path: &Path, section: &Section) -> Result<(), VdfError> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    fs::write(&tmp_path, to_text(section)).map_err(|e| VdfError::Io(e.to_string()))?;
+    fs::rename(&tmp_path, path).map_err(|e| VdfError::Io(e.to_string()))
+}
+
+pub fn read_binary_file(path: &Path) -> Result<Section, VdfError> {
+    let content = fs::read(path).map_err(|e| VdfError::Io(e.to_string()))?;
+    parse_binary(&content)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Str(String),
+    Open,
+    Close,
+    Condition(String),
+    Comment(String),
+    Newline,
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, VdfError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => {
+                tokens.push((Token::Newline, line));
+                line += 1;
+            }
+            c if c.is_whitespace() || c == '\u{feff}' => {}
+            '{' => tokens.push((Token::Open, line)),
+            '}' => tokens.push((Token::Close, line)),
+            '/' if chars.peek() == Some(&'/') => {
+                chars.next();
+                let mut comment = String::new();
+                while let Some(&next) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    comment.push(next);
+                    chars.next();
+                }
+                tokens.push((
+                    Token::Comment(comment.trim_end_matches('\r').to_string()),
+                    line,
+                ));
+            }
+            '[' => {
+                let mut condition = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some('\n') | None => {
+                            return Err(VdfError::Syntax {
+                                line,
+                                message: String::from("unterminated conditional"),
+                            })
+                        }
+                        Some(next) => condition.push(next),
+                    }
+                }
+                tokens.push((Token::Condition(condition), line));
+            }
+            '"' => {
+                let start = line;
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some('\\') => value.push('\\'),
+                            Some('"') => value.push('"'),
+                            Some(other) => {
+                                value.push('\\');
+                                value.push(other);
+                            }
+                            None => return Err(VdfError::UnexpectedEof),
+                        },
+                        Some(next) => {
+                            if next == '\n' {
+                                line += 1;
+                            }
+                            value.push(next);
+                        }
+                        None => return Err(VdfError::UnexpectedEof),
+                    }
+                }
+                tokens.push((Token::Str(value), start));
+            }
+            c => {
+                let mut value = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '{' | '}' | '"') {
+                        break;
+                    }
+                    value.push(next);
+                    chars.next();
+                }
+                tokens.push((Token::Str(value), line));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Option<(Token, usize)> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn trailing_comment(&mut self) -> Option<String> {
+        if let Some(Token::Comment(comment)) = self.peek() {
+            let comment = comment.clone();
+            self.pos += 1;
+            Some(comment)
+        } else {
+            None
+        }
+    }
+
+    fn condition(&mut self) -> Option<String> {
+        if let Some(Token::Condition(condition)) = self.peek() {
+            let condition = condition.clone();
+            self.pos += 1;
+            Some(condition)
+        } else {
+            None
+        }
+    }
+
+    fn section(&mut self, root: bool) -> Result<Section, VdfError> {
+        let mut section = Section::new();
+        // A file starting with a newline starts with a blank line, a section
+        // body starts on the line of its `{`.
+        let mut previous_newline = root;
+
+        loop {
+            let (token, line) = match self.next() {
+                Some(token) => token,
+                None if root => break,
+                None => return Err(VdfError::UnexpectedEof),
+            };
+
+            let is_newline = token == Token::Newline;
+            match token {
+                Token::Newline => {
+                    if previous_newline {
+                        section.items.push(Item::Blank);
+                    }
+                }
+                Token::Comment(comment) => section.items.push(Item::Comment(comment)),
+                Token::Close if !root => break,
+                Token::Str(key) => {
+                    let entry = self.entry(key)?;
+                    section.items.push(Item::Entry(entry));
+                }
+                Token::Open | Token::Close | Token::Condition(_) => {
+                    return Err(VdfError::Syntax {
+                        line,
+                        message: String::from("expected a key"),
+                    })
+                }
+            }
+            previous_newline = is_newline;
+        }
+
+        if root {
+            // The newline ending the last line is implied by the writer.
+            if let Some(Item::Blank) = section.items.last() {
+                section.items.pop();
+            }
+        }
+
+        Ok(section)
+    }
+
+    fn entry(&mut self, key: String) -> Result<Entry, VdfError> {
+        let mut condition = self.condition();
+        let mut key_comment = None;
+        let mut before_value = Vec::new();
+        let mut newlines = 0;
+
+        loop {
+            let value = match self.next() {
+                Some((Token::Newline, _)) => {
+                    if newlines > 0 {
+                        before_value.push(Item::Blank);
+                    }
+                    newlines += 1;
+                    continue;
+                }
+                Some((Token::Comment(comment), _)) => {
+                    if key_comment.is_none() && before_value.is_empty() && newlines == 0 {
+                        key_comment = Some(comment);
+                    } else {
+                        before_value.push(Item::Comment(comment));
+                    }
+                    newlines = 0;
+                    continue;
+                }
+                Some((Token::Condition(c), _)) => {
+                    condition = Some(c);
+                    continue;
+                }
+                Some((Token::Str(value), _)) => Value::String(value),
+                Some((Token::Open, _)) => Value::Section(self.section(false)?),
+                Some((Token::Close, line)) => {
+                    return Err(VdfError::Syntax {
+                        line,
+                        message: format!("missing value for key \"{}\"", key),
+                    })
+                }
+                None => return Err(VdfError::UnexpectedEof),
+            };
+            let condition = self.condition().or(condition);
+            let comment = self.trailing_comment();
+            return Ok(Entry {
+                key,
+                value,
+                condition,
+                comment,
+                key_comment,
+                before_value,
+            });
+        }
+    }
+}
+
+pub fn parse_text(input: &str) -> Result<Section, VdfError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+    };
+    parser.section(true)
+}
+
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn write_suffix(out: &mut String, entry: &Entry) {
+    if let Some(condition) = &entry.condition {
+        out.push_str(&format!(" [{}]", condition));
+    }
+    if let Some(comment) = &entry.comment {
+        out.push_str(&format!("\t//{}", comment));
+    }
+    out.push('\n');
+}
+
+fn write_items(out: &mut String, items: &[Item], depth: usize) {
+    let indent = "\t".repeat(depth);
+
+    for item in items {
+        match item {
+            Item::Blank => out.push('\n'),
+            Item::Comment(comment) => out.push_str(&format!("{}//{}\n", indent, comment)),
+            Item::Entry(entry) => {
+                out.push_str(&format!("{}{}", indent, quote(&entry.key)));
+                // Anything between the key and its value moves the value to a
+                // line of its own.
+                let split = entry.key_comment.is_some() || !entry.before_value.is_empty();
+                if split {
+                    if let Some(comment) = &entry.key_comment {
+                        out.push_str(&format!("\t//{}", comment));
+                    }
+                    out.push('\n');
+                    write_items(out, &entry.before_value, depth);
+                }
+                match &entry.value {
+                    Value::Section(child) => {
+                        if !split {
+                            out.push('\n');
+                        }
+                        out.push_str(&format!("{}{{\n", indent));
+                        write_items(out, &child.items, depth + 1);
+                        out.push_str(&format!("{}}}", indent));
+                    }
+                    value if split => {
+                        out.push_str(&format!("{}\t\t{}", indent, quote(&value.to_text())))
+                    }
+                    value => out.push_str(&format!("\t\t{}", quote(&value.to_text()))),
+                }
+                write_suffix(out, entry);
+            }
+        }
+    }
+}
+
+pub fn to_text(section: &Section) -> String {
+    let mut out = String::new();
+    write_items(&mut out, &section.items, 0);
+    out
+}
+
+const BIN_SECTION: u8 = 0x00;
+const BIN_STRING: u8 = 0x01;
+const BIN_INT32: u8 = 0x02;
+const BIN_FLOAT32: u8 = 0x03;
+const BIN_POINTER: u8 = 0x04;
+const BIN_WIDE_STRING: u8 = 0x05;
+const BIN_COLOR: u8 = 0x06;
+const BIN_UINT64: u8 = 0x07;
+const BIN_END: u8 = 0x08;
+const BIN_INT64: u8 = 0x0A;
+const BIN_END_ALT: u8 = 0x0B;
+
+struct BinaryReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BinaryReader<'a> {
+    fn error(&self, message: &str) -> VdfError {
+        VdfError::Binary {
+            offset: self.pos,
+            message: message.to_string(),
+        }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], VdfError> {
+        if self.pos + len > self.data.len() {
+            return Err(VdfError::UnexpectedEof);
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, VdfError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], VdfError> {
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(self.take(N)?);
+        Ok(bytes)
+    }
+
+    fn string(&mut self) -> Result<String, VdfError> {
+        let rest = &self.data[self.pos..];
+        let end = rest
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or(VdfError::UnexpectedEof)?;
+        let value = String::from_utf8_lossy(&rest[..end]).into_owned();
+        self.pos += end + 1;
+        Ok(value)
+    }
+
+    fn wide_string(&mut self) -> Result<String, VdfError> {
+        let mut units = Vec::new();
+        loop {
+            let unit = u16::from_le_bytes(self.array()?);
+            if unit == 0 {
+                break;
+            }
+            units.push(unit);
+        }
+        String::from_utf16(&units).map_err(|_| self.error("invalid wide string"))
+    }
+
+    fn section(&mut self, root: bool) -> Result<Section, VdfError> {
+        let mut section = Section::new();
+
+        loop {
+            if root && self.pos == self.data.len() {
+                break;
+            }
+            let kind = self.byte()?;
+            if kind == BIN_END || kind == BIN_END_ALT {
+                break;
+            }
+
+            let key = self.string()?;
+            let value = match kind {
+                BIN_SECTION => Value::Section(self.section(false)?),
+                BIN_STRING => Value::String(self.string()?),
+                BIN_INT32 => Value::Int32(i32::from_le_bytes(self.array()?)),
+                BIN_FLOAT32 => Value::Float32(f32::from_le_bytes(self.array()?)),
+                BIN_POINTER => Value::Pointer(i32::from_le_bytes(self.array()?)),
+                BIN_WIDE_STRING => Value::WideString(self.wide_string()?),
+                BIN_COLOR => Value::Color(i32::from_le_bytes(self.array()?)),
+                BIN_UINT64 => Value::UInt64(u64::from_le_bytes(self.array()?)),
+                BIN_INT64 => Value::Int64(i64::from_le_bytes(self.array()?)),
+                _ => return Err(self.error(&format!("unknown type byte {:#04x}", kind))),
+            };
+            section.push(&key, value);
+        }
+
+        Ok(section)
+    }
+}
+
+pub fn parse_binary(data: &[u8]) -> Result<Section, VdfError> {
+    let mut reader = BinaryReader { data, pos: 0 };
+    reader.section(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r##"
+"InstallConfigStore"
+{
+	// Written by Steam
+	"Software"
+	{
+		"Valve"
+		{
+			"Steam"
+			{
+				"AutoUpdateWindowEnabled"		"0"
+
+				"CompatToolMapping"	// per app
+				{
+					"0"
+					{
+						"name"		"GE-Proton9-4"
+						"config"		""
+						"priority"		"75"
+					}
+				}
+				"SteamDefaultDialog"		"#app_games" [$WIN32]
+				"Rate"		"30000"	// bytes
+			}
+		}
+	}
+}
+"##;
+
+    #[test]
+    fn text_round_trip_keeps_layout() {
+        let section = parse_text(CONFIG).unwrap();
+        assert_eq!(to_text(&section), CONFIG);
+    }
+
+    #[test]
+    fn text_round_trip_keeps_comments_between_key_and_section() {
+        let input = "\"a\"\n// first\n\n// second\n{\n\t\"b\"\t\t\"1\"\n}\n";
+        let section = parse_text(input).unwrap();
+        assert_eq!(to_text(&section), input);
+    }
+
+    #[test]
+    fn text_reads_values_and_conditions() {
+        let section = parse_text(CONFIG).unwrap();
+        let steam = section
+            .path(&["InstallConfigStore", "Software", "Valve", "Steam"])
+            .unwrap();
+        let tool = steam.path(&["CompatToolMapping", "0"]).unwrap();
+        assert_eq!(tool.get_str("NAME"), Some("GE-Proton9-4"));
+        assert_eq!(tool.get("priority").and_then(Value::as_i64), Some(75));
+        let dialog = steam
+            .entries()
+            .find(|entry| entry.key == "SteamDefaultDialog")
+            .unwrap();
+        assert_eq!(dialog.condition.as_deref(), Some("$WIN32"));
+    }
+
+    #[test]
+    fn text_edit_keeps_position_and_comment() {
+        let mut section = parse_text(CONFIG).unwrap();
+        section
+            .path_entry(&["InstallConfigStore", "Software", "Valve", "Steam"])
+            .set("Rate", "50000");
+        let text = to_text(&section);
+        assert!(text.contains("\"Rate\"\t\t\"50000\"\t// bytes\n\t\t\t}"));
+    }
+
+    #[test]
+    fn text_errors() {
+        assert!(matches!(
+            parse_text("\"a\"\t\t\"unterminated\n"),
+            Err(VdfError::UnexpectedEof)
+        ));
+        assert!(matches!(
+            parse_text("\"a\"\n{\n\t\"b\"\t\t\"1\"\n"),
+            Err(VdfError::UnexpectedEof)
+        ));
+        assert!(matches!(
+            parse_text("\"a\"\n}\n"),
+            Err(VdfError::Syntax { line: 2, .. })
+        ));
+    }
+
+    fn binary_entry(out: &mut Vec<u8>, kind: u8, key: &str) {
+        out.push(kind);
+        out.extend_from_slice(key.as_bytes());
+        out.push(0);
+    }
+
+    #[test]
+    fn binary_shortcuts() {
+        let mut data = Vec::new();
+        binary_entry(&mut data, BIN_SECTION, "shortcuts");
+        binary_entry(&mut data, BIN_SECTION, "0");
+        binary_entry(&mut data, BIN_INT32, "appid");
+        data.extend_from_slice(&(-1234i32).to_le_bytes());
+        binary_entry(&mut data, BIN_STRING, "AppName");
+        data.extend_from_slice(b"Game\0");
+        binary_entry(&mut data, BIN_SECTION, "tags");
+        binary_entry(&mut data, BIN_STRING, "0");
+        data.extend_from_slice(b"favorite\0");
+        data.extend_from_slice(&[BIN_END, BIN_END, BIN_END, BIN_END]);
+
+        let section = parse_binary(&data).unwrap();
+        let shortcut = section.path(&["shortcuts", "0"]).unwrap();
+        assert_eq!(shortcut.get("appid"), Some(&Value::Int32(-1234)));
+        assert_eq!(shortcut.get_str("AppName"), Some("Game"));
+        assert_eq!(
+            shortcut.path(&["tags"]).unwrap().get_str("0"),
+            Some("favorite")
+        );
+    }
+
+    #[test]
+    fn binary_errors() {
+        let mut data = Vec::new();
+        binary_entry(&mut data, BIN_SECTION, "shortcuts");
+        binary_entry(&mut data, BIN_STRING, "AppName");
+        assert!(matches!(parse_binary(&data), Err(VdfError::UnexpectedEof)));
+        assert!(matches!(
+            parse_binary(b"\x09key\0"),
+            Err(VdfError::Binary { .. })
+        ));
+    }
+}
@@ -0,0 +1,188 @@
+use regex::Regex;
+use std::cmp::Ordering;
+use std::sync::OnceLock;
+
+/// A release version of a GE build, parsed from a tag or folder name.
+///
+/// Handles the current `GE-Proton9-4` scheme, the older `Proton-6.21-GE-2` /
+/// `6.21-GE-2` one, Wine-GE's `lutris-GE-Proton8-26-x86_64` folders and
+/// variant suffixes such as `GE-Proton8-26-LoL`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u32,
+    /// Only the older scheme has a minor version.
+    pub minor: Option<u32>,
+    pub build: u32,
+    /// Release candidate number, sorts before the final build.
+    pub rc: Option<u32>,
+    /// Builds of a different variant, e.g. `LoL`, don't update each other.
+    pub variant: Option<String>,
+}
+
+const MODERN: &str = r"^(?:wine-)?(?:lutris-)?GE-Proton(\d+)-(\d+)((?:-\w+)*?)(?:-x86_64)?$";
+const LEGACY: &str = r"^(?:Proton-|wine-)?(?:lutris-)?(?:GE-|ge-)?(\d+)\.(\d+)-(?:GE-|ge-)?(\d+)((?:-\w+)*?)(?:-x86_64)?$";
+
+/// Compiled once, names are parsed for every release and installed tool.
+fn regex(cell: &'static OnceLock<Option<Regex>>, pattern: &str) -> Option<&'static Regex> {
+    cell.get_or_init(|| Regex::new(pattern).ok()).as_ref()
+}
+
+impl Version {
+    pub fn parse(name: &str) -> Option<Version> {
+        static MODERN_RE: OnceLock<Option<Regex>> = OnceLock::new();
+        static LEGACY_RE: OnceLock<Option<Regex>> = OnceLock::new();
+
+        let name = name.trim();
+        if let Some(captures) = regex(&MODERN_RE, MODERN)?.captures(name) {
+            let (rc, variant) = suffix(&captures[3]);
+            return Some(Version {
+                major: captures[1].parse().ok()?,
+                minor: None,
+                build: captures[2].parse().ok()?,
+                rc,
+                variant,
+            });
+        }
+        let captures = regex(&LEGACY_RE, LEGACY)?.captures(name)?;
+        let (rc, variant) = suffix(&captures[4]);
+        Some(Version {
+            major: captures[1].parse().ok()?,
+            minor: Some(captures[2].parse().ok()?),
+            build: captures[3].parse().ok()?,
+            rc,
+            variant,
+        })
+    }
+
+    /// Whether `other` is a build of the same variant, so one can replace
+    /// the other.
+    pub fn same_variant(&self, other: &Version) -> bool {
+        self.variant == other.variant
+    }
+}
+
+/// Splits `-rc2-LoL` style suffixes into the release candidate number and
+/// the variant name.
+fn suffix(suffix: &str) -> (Option<u32>, Option<String>) {
+    let mut rc = None;
+    let mut variant: Vec<&str> = Vec::new();
+    for part in suffix.split('-').filter(|part| !part.is_empty()) {
+        match part
+            .strip_prefix("rc")
+            .and_then(|number| number.parse().ok())
+        {
+            Some(number) if rc.is_none() => rc = Some(number),
+            _ => variant.push(part),
+        }
+    }
+    let variant = if variant.is_empty() {
+        None
+    } else {
+        Some(variant.join("-"))
+    };
+    (rc, variant)
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // The older scheme ended with 7.x, `GE-Proton7-1` came after it.
+        let key = |version: &Version| {
+            (
+                version.major,
+                version.minor.is_none(),
+                version.minor,
+                version.build,
+                version.rc.is_none(),
+                version.rc,
+            )
+        };
+        key(self)
+            .cmp(&key(other))
+            .then_with(|| self.variant.cmp(&other.variant))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(name: &str) -> Version {
+        Version::parse(name).unwrap_or_else(|| panic!("{} should parse", name))
+    }
+
+    #[test]
+    fn parses_modern_tags() {
+        let version = parse("GE-Proton9-4");
+        assert_eq!((version.major, version.minor, version.build), (9, None, 4));
+        assert_eq!((version.rc, version.variant), (None, None));
+    }
+
+    #[test]
+    fn parses_legacy_tags() {
+        for name in ["Proton-6.21-GE-2", "6.21-GE-2"] {
+            let version = parse(name);
+            assert_eq!(
+                (version.major, version.minor, version.build),
+                (6, Some(21), 2)
+            );
+        }
+    }
+
+    #[test]
+    fn parses_wine_ge_folders() {
+        for name in [
+            "lutris-GE-Proton8-26-x86_64",
+            "wine-lutris-GE-Proton8-26-x86_64",
+        ] {
+            assert_eq!(parse(name), parse("GE-Proton8-26"));
+        }
+    }
+
+    #[test]
+    fn parses_suffixes() {
+        let rc = parse("GE-Proton8-26-rc2");
+        assert_eq!((rc.rc, rc.variant.as_deref()), (Some(2), None));
+
+        let lol = parse("GE-Proton8-26-LoL");
+        assert_eq!((lol.rc, lol.variant.as_deref()), (None, Some("LoL")));
+        assert!(!lol.same_variant(&parse("GE-Proton8-26")));
+    }
+
+    #[test]
+    fn rejects_other_names() {
+        for name in ["", "Proton 8.0", "GE-Proton", "custom-build"] {
+            assert_eq!(Version::parse(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn orders_by_major_then_build() {
+        assert!(parse("GE-Proton9-4") > parse("GE-Proton8-32"));
+        assert!(parse("GE-Proton8-32") > parse("GE-Proton8-4"));
+    }
+
+    #[test]
+    fn orders_modern_after_legacy() {
+        assert!(parse("GE-Proton7-1") > parse("Proton-6.21-GE-2"));
+        assert!(parse("GE-Proton7-1") > parse("Proton-7.0-GE-1"));
+    }
+
+    #[test]
+    fn orders_release_candidates_before_the_build() {
+        assert!(parse("GE-Proton8-26-rc2") < parse("GE-Proton8-26"));
+        assert!(parse("GE-Proton8-26-rc1") < parse("GE-Proton8-26-rc2"));
+        assert!(parse("GE-Proton8-26-rc2") > parse("GE-Proton8-25"));
+    }
+
+    #[test]
+    fn orders_variants_after_the_plain_build() {
+        assert!(parse("GE-Proton8-26-LoL") > parse("GE-Proton8-26"));
+        assert!(parse("GE-Proton8-26-LoL") < parse("GE-Proton8-27"));
+    }
+}